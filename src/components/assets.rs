@@ -1,21 +1,22 @@
 use crate::components::animation::*;
+use bevy::asset::LoadedFolder;
 use bevy::prelude::*;
+use serde::Deserialize;
 
 #[derive(Resource)]
 pub struct GameAssets {
     pub player_texture: Handle<Image>,
-    pub dino_assets: AnimAsset,
     pub explosion_assets: AnimAsset,
     pub fill_heart_texture: Handle<Image>,
     pub empty_heart_texture: Handle<Image>,
-    pub rapid_fire_texture: Handle<Image>,
+    pub power_up_texture: Handle<Image>,
     pub piercing_shot_texture: Handle<Image>,
     pub apple_texture: Handle<Image>,
-    pub blue_egg_texture: Handle<Image>,
+    pub bomb_texture: Handle<Image>,
+    pub weapon_texture: Handle<Image>,
     pub yellow_egg_texture: Handle<Image>,
-    pub ground_texture: Handle<Image>,
-    pub ocean_texture: Handle<Image>,
-    pub universe_texture: Handle<Image>,
+    pub stage_folder: Handle<LoadedFolder>,
+    pub enemy_folder: Handle<LoadedFolder>,
 
     pub shoot_sound: Handle<bevy_kira_audio::AudioSource>,
     pub damage_sound: Handle<bevy_kira_audio::AudioSource>,
@@ -36,3 +37,19 @@ pub enum AssetVisual {
     Static(Handle<Image>),
     Animated(AnimAsset),
 }
+
+/// Sprite description as written in data files. Animated sprites are laid out as a
+/// `columns` x `rows` grid of `tile_size` frames played in a loop.
+#[derive(Deserialize, Clone, Debug)]
+pub enum VisualAsset {
+    Static {
+        texture: String,
+    },
+    Animated {
+        texture: String,
+        tile_size: (u32, u32),
+        columns: u32,
+        rows: u32,
+        fps: u8,
+    },
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::bullet::BulletPattern;
+
+/// Boss at the end of a stage, as written in the `boss` field of a `*.stage.ron` file.
+/// Its hp, sprite and bullet come from the enemy definition named by `enemy`.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct BossSetting {
+    pub enemy: String,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Extra hitboxes in world units, relative to the boss centre.
+    #[serde(default)]
+    pub parts: Vec<BossColliderPart>,
+    pub phases: Vec<BossPhase>,
+    #[serde(default)]
+    pub score_bonus: u32,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct BossColliderPart {
+    pub offset: (f32, f32),
+    pub size: (f32, f32),
+}
+
+/// A phase starts once the boss hp falls to `hp_threshold` (a fraction of max hp) or below.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct BossPhase {
+    pub hp_threshold: f32,
+    pub movement: BossMovePattern,
+    pub fire_interval: f32,
+    pub bullet_pattern: BulletPattern,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum BossMovePattern {
+    Hover,
+    Sway { amplitude: f32, frequency: f32 },
+    Circle { radius: f32, frequency: f32 },
+    Chase { speed: f32 },
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub phases: Vec<BossPhase>,
+    pub phase_index: usize,
+    pub home: Vec2,
+    pub entered: bool,
+    pub elapsed: f32,
+}
+
+/// Additional hitbox that follows its boss. Hits on it damage the boss.
+#[derive(Component)]
+pub struct BossPart {
+    pub boss: Entity,
+    pub offset: Vec2,
+}
+
+#[derive(Component)]
+pub struct BossHpBar;
+
+#[derive(Component)]
+pub struct BossHpBarFill;
+
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossEncounter {
+    Pending,
+    Fighting { score_bonus: u32 },
+    Defeated,
+}
@@ -0,0 +1,76 @@
+use crate::components::collider::WorldShape;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+pub const GRID_CELL_SIZE: f32 = 64.0;
+
+/// Uniform grid broadphase. Colliders are bucketed by the cells their bounds cover and
+/// only colliders sharing a cell are paired up. Rebuilt from scratch every frame.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    pub cell_size: f32,
+    cells: HashMap<IVec2, Vec<usize>>,
+    bounds: Vec<Rect>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self {
+            cell_size: GRID_CELL_SIZE,
+            cells: HashMap::new(),
+            bounds: Vec::new(),
+        }
+    }
+}
+
+impl SpatialGrid {
+    pub fn rebuild(&mut self, shapes: impl Iterator<Item = WorldShape>) {
+        // Keep the buckets that were used last frame so their allocations are reused.
+        self.cells.retain(|_, indices| {
+            let used = !indices.is_empty();
+            indices.clear();
+            used
+        });
+        self.bounds.clear();
+
+        for (index, shape) in shapes.enumerate() {
+            let bounds = shape.bounds();
+            let min = self.cell_of(bounds.min);
+            let max = self.cell_of(bounds.max);
+            for x in min.x..=max.x {
+                for y in min.y..=max.y {
+                    self.cells.entry(IVec2::new(x, y)).or_default().push(index);
+                }
+            }
+            self.bounds.push(bounds);
+        }
+    }
+
+    /// Index pairs whose bounds overlap, each pair reported once with the lower index
+    /// first. The caller still runs the exact shape test on them.
+    pub fn pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+
+        for (cell, indices) in self.cells.iter() {
+            for (n, &i) in indices.iter().enumerate() {
+                for &j in &indices[n + 1..] {
+                    let (a, b) = (self.bounds[i], self.bounds[j]);
+                    if a.intersect(b).is_empty() {
+                        continue;
+                    }
+                    // Pairs spanning several cells are only reported by the cell holding
+                    // the lower corner of their overlap.
+                    if self.cell_of(a.min.max(b.min)) == *cell {
+                        pairs.push((i, j));
+                    }
+                }
+            }
+        }
+
+        pairs
+    }
+
+    fn cell_of(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+}
@@ -1,16 +1,19 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 
-use super::assets::AssetVisual;
+use super::assets::{AssetVisual, VisualAsset};
 
 #[derive(Component)]
 pub struct Bullet {
-    pub is_player: bool,
-    pub speed: f32,
+    pub velocity: Vec2,
+    pub damage: u32,
+    pub piercing: bool,
+    /// Enemies this bullet has already damaged, so a piercing shot hits each enemy once.
+    pub hits: Vec<Entity>,
 }
 
 #[derive(Clone)]
 pub struct BulletDefinition {
-    pub is_player: bool,
     pub speed: f32,
     pub damage: u32,
     pub collider_size: Vec2,
@@ -18,7 +21,106 @@ pub struct BulletDefinition {
     pub visual: AssetVisual,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+pub struct BulletAsset {
+    pub speed: f32,
+    pub damage: u32,
+    pub collider_size: (f32, f32),
+
+    pub visual: VisualAsset,
+}
+
+/// How an enemy arranges the bullets of one volley. Angles are in degrees, measured from
+/// straight down (or from the direction to the player for aimed patterns).
+#[derive(Deserialize, Clone, Debug, PartialEq, Default)]
+pub enum BulletPattern {
+    #[default]
+    Straight,
+    Aimed,
+    Spread {
+        count: u32,
+        angle: f32,
+        aimed: bool,
+    },
+    Ring {
+        count: u32,
+    },
+    /// A ring that turns by `rotation` degrees after every volley.
+    Spiral {
+        count: u32,
+        rotation: f32,
+    },
+    /// Fires `pattern` `shots` times, `delay` seconds apart.
+    Burst {
+        pattern: Box<BulletPattern>,
+        shots: u32,
+        delay: f32,
+    },
+}
+
+impl BulletPattern {
+    /// The first burst `delay` that is not positive, looking into nested bursts too.
+    pub fn invalid_burst_delay(&self) -> Option<f32> {
+        match self {
+            BulletPattern::Burst { pattern, delay, .. } => {
+                if *delay <= 0.0 {
+                    Some(*delay)
+                } else {
+                    pattern.invalid_burst_delay()
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Per-enemy state needed by spirals and bursts.
+#[derive(Component, Default)]
+pub struct BulletEmitter {
+    pub spiral_angle: f32,
+    pub burst_left: u32,
+    pub burst_timer: Timer,
+}
+
+/// Steers a player missile toward `target`, turning at most `turn_rate` radians per
+/// second. A missile without a live target picks the nearest enemy, and is removed once
+/// `lifetime` runs out.
+#[derive(Component)]
+pub struct Homing {
+    pub target: Option<Entity>,
+    pub turn_rate: f32,
+    pub lifetime: Timer,
+}
+
 #[derive(Resource)]
 pub struct BulletCooldown {
     pub timer: Timer,
 }
+
+/// How long Fire has been held. Past `DELAY` a charge builds, and once it reaches
+/// `MIN_LEVEL` the normal gun stops and letting go fires a charge shot scaled by `level`.
+#[derive(Resource, Default)]
+pub struct ChargeShot {
+    pub held: f32,
+    pub full_cue_played: bool,
+}
+
+impl ChargeShot {
+    pub const DELAY: f32 = 0.3;
+    pub const FULL: f32 = 1.2;
+    /// Below this level a hold still counts as normal fire.
+    pub const MIN_LEVEL: f32 = 0.1;
+
+    pub fn is_charging(&self) -> bool {
+        self.level() >= Self::MIN_LEVEL
+    }
+
+    /// 0 until the charge starts, rising to 1 after another `FULL` seconds.
+    pub fn level(&self) -> f32 {
+        ((self.held - Self::DELAY) / Self::FULL).clamp(0.0, 1.0)
+    }
+}
+
+/// The glow at the muzzle that grows while a charge builds.
+#[derive(Component)]
+pub struct ChargeGlow;
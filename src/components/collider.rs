@@ -3,21 +3,553 @@ use bevy::prelude::*;
 #[derive(Component)]
 pub struct Collider {
     pub shape: ColliderShape,
-    pub tag: ColliderTag,
+    /// What this collider is.
+    pub layer: CollisionLayers,
+    /// What this collider collides with.
+    pub mask: CollisionLayers,
 }
 
 #[derive(Copy, Clone)]
 pub enum ColliderShape {
-    Circle { radius: f32 },
-    Rectangle { size: Vec2 },
-    Capsule { size: Vec2 }, // width: size.x, height: size.y
+    Circle {
+        radius: f32,
+    },
+    Rectangle {
+        size: Vec2,
+    },
+    Capsule {
+        size: Vec2,
+    }, // width: size.x, height: size.y
+    /// A thick line running `length` along the local y axis from the entity's origin.
+    /// Used for beams.
+    Segment {
+        length: f32,
+        width: f32,
+    },
 }
 
-#[derive(Copy, Clone)]
-pub enum ColliderTag {
-    Player,
-    Enemy,
-    Bullet,
-    Item,
-    EnemyBullet,
+/// Bitset of collision layers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct CollisionLayers(pub u32);
+
+impl CollisionLayers {
+    pub const PLAYER: CollisionLayers = CollisionLayers(1 << 0);
+    pub const ENEMY: CollisionLayers = CollisionLayers(1 << 1);
+    pub const PLAYER_BULLET: CollisionLayers = CollisionLayers(1 << 2);
+    pub const ENEMY_BULLET: CollisionLayers = CollisionLayers(1 << 3);
+    pub const ITEM: CollisionLayers = CollisionLayers(1 << 4);
+
+    pub fn intersects(self, other: CollisionLayers) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl std::ops::BitOr for CollisionLayers {
+    type Output = CollisionLayers;
+
+    fn bitor(self, other: CollisionLayers) -> CollisionLayers {
+        CollisionLayers(self.0 | other.0)
+    }
+}
+
+impl Collider {
+    /// A pair is only reported when each side's mask accepts the other's layer.
+    pub fn interacts_with(&self, other: &Collider) -> bool {
+        self.mask.intersects(other.layer) && other.mask.intersects(self.layer)
+    }
+}
+
+/// Sent by `collision_system` for every overlapping pair that passes the mask test.
+/// `kinds` holds the layers of `a` and `b`.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct CollisionEvent {
+    pub a: Entity,
+    pub b: Entity,
+    pub kinds: (CollisionLayers, CollisionLayers),
+}
+
+impl CollisionEvent {
+    /// The two entities ordered as (`first` side, `second` side), regardless of which one
+    /// the event listed first.
+    pub fn between(
+        &self,
+        first: CollisionLayers,
+        second: CollisionLayers,
+    ) -> Option<(Entity, Entity)> {
+        let (kind_a, kind_b) = self.kinds;
+        if kind_a.intersects(first) && kind_b.intersects(second) {
+            Some((self.a, self.b))
+        } else if kind_b.intersects(first) && kind_a.intersects(second) {
+            Some((self.b, self.a))
+        } else {
+            None
+        }
+    }
+}
+
+/// A collider resolved into world space, with the entity's translation, rotation and
+/// scale applied.
+#[derive(Copy, Clone, Debug)]
+pub enum WorldShape {
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    /// Oriented box. `axis` is the box's local x axis in world space.
+    Box {
+        center: Vec2,
+        half_size: Vec2,
+        axis: Vec2,
+    },
+    /// Every point within `radius` of the segment `a`-`b`.
+    Capsule {
+        a: Vec2,
+        b: Vec2,
+        radius: f32,
+    },
+}
+
+impl Collider {
+    pub fn world_shape(&self, transform: &Transform) -> WorldShape {
+        let center = transform.translation.xy();
+        let scale = transform.scale.xy().abs();
+        let axis = (transform.rotation * Vec3::X)
+            .xy()
+            .try_normalize()
+            .unwrap_or(Vec2::X);
+
+        match self.shape {
+            ColliderShape::Circle { radius } => WorldShape::Circle {
+                center,
+                radius: radius * scale.max_element(),
+            },
+            ColliderShape::Rectangle { size } => WorldShape::Box {
+                center,
+                half_size: size * scale / 2.0,
+                axis,
+            },
+            ColliderShape::Capsule { size } => {
+                // The capsule runs along its longer side; the shorter side is the diameter.
+                let size = size * scale;
+                let (radius, half) = if size.y >= size.x {
+                    let radius = size.x / 2.0;
+                    (radius, axis.perp() * (size.y / 2.0 - radius))
+                } else {
+                    let radius = size.y / 2.0;
+                    (radius, axis * (size.x / 2.0 - radius))
+                };
+                WorldShape::Capsule {
+                    a: center - half,
+                    b: center + half,
+                    radius,
+                }
+            }
+            ColliderShape::Segment { length, width } => WorldShape::Capsule {
+                a: center,
+                b: center + axis.perp() * length * scale.y,
+                radius: width * scale.x / 2.0,
+            },
+        }
+    }
+}
+
+impl WorldShape {
+    pub fn overlaps(&self, other: &WorldShape) -> bool {
+        match (self.as_capsule(), other.as_capsule()) {
+            (Some((a1, b1, r1)), Some((a2, b2, r2))) => {
+                segment_segment_distance(a1, b1, a2, b2) < r1 + r2
+            }
+            (Some((a, b, radius)), None) => other.box_segment_distance(a, b) < radius,
+            (None, Some((a, b, radius))) => self.box_segment_distance(a, b) < radius,
+            (None, None) => self.boxes_overlap(other),
+        }
+    }
+
+    /// How far a capsule of `radius` can be swept from `origin` along the unit vector
+    /// `direction` before it touches this shape, or `None` if it stays clear for
+    /// `max_distance`.
+    pub fn sweep_distance(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        radius: f32,
+        max_distance: f32,
+    ) -> Option<f32> {
+        let sweep = |distance: f32| WorldShape::Capsule {
+            a: origin,
+            b: origin + direction * distance,
+            radius,
+        };
+        if !sweep(max_distance).overlaps(self) {
+            return None;
+        }
+        if sweep(0.0).overlaps(self) {
+            return Some(0.0);
+        }
+
+        // Overlap only ever starts once as the capsule grows, so bisect for it.
+        let (mut clear, mut hit) = (0.0, max_distance);
+        for _ in 0..16 {
+            let middle = (clear + hit) / 2.0;
+            if sweep(middle).overlaps(self) {
+                hit = middle;
+            } else {
+                clear = middle;
+            }
+        }
+        Some(hit)
+    }
+
+    /// Axis-aligned bounding box, used by the broadphase.
+    pub fn bounds(&self) -> Rect {
+        match *self {
+            WorldShape::Circle { center, radius } => {
+                Rect::from_center_half_size(center, Vec2::splat(radius))
+            }
+            WorldShape::Box {
+                center,
+                half_size,
+                axis,
+            } => {
+                let extent = (axis * half_size.x).abs() + (axis.perp() * half_size.y).abs();
+                Rect::from_center_half_size(center, extent)
+            }
+            WorldShape::Capsule { a, b, radius } => Rect::from_corners(a, b).inflate(radius),
+        }
+    }
+
+    /// Circles are capsules whose segment is a single point.
+    fn as_capsule(&self) -> Option<(Vec2, Vec2, f32)> {
+        match *self {
+            WorldShape::Circle { center, radius } => Some((center, center, radius)),
+            WorldShape::Capsule { a, b, radius } => Some((a, b, radius)),
+            WorldShape::Box { .. } => None,
+        }
+    }
+
+    /// Separating axis test between two oriented boxes.
+    fn boxes_overlap(&self, other: &WorldShape) -> bool {
+        let (
+            WorldShape::Box {
+                center: c1,
+                half_size: h1,
+                axis: x1,
+            },
+            WorldShape::Box {
+                center: c2,
+                half_size: h2,
+                axis: x2,
+            },
+        ) = (*self, *other)
+        else {
+            return false;
+        };
+
+        let projected = |half: Vec2, axis: Vec2, n: Vec2| {
+            half.x * axis.dot(n).abs() + half.y * axis.perp().dot(n).abs()
+        };
+        let offset = c2 - c1;
+
+        [x1, x1.perp(), x2, x2.perp()]
+            .into_iter()
+            .all(|n| offset.dot(n).abs() < projected(h1, x1, n) + projected(h2, x2, n))
+    }
+
+    /// Distance from a box to the segment `a`-`b`, zero when they touch.
+    fn box_segment_distance(&self, a: Vec2, b: Vec2) -> f32 {
+        let WorldShape::Box {
+            center,
+            half_size,
+            axis,
+        } = *self
+        else {
+            return f32::MAX;
+        };
+
+        let to_local = |p: Vec2| {
+            let d = p - center;
+            Vec2::new(d.dot(axis), d.dot(axis.perp()))
+        };
+        let (a, b) = (to_local(a), to_local(b));
+
+        if segment_hits_box(a, b, half_size) {
+            return 0.0;
+        }
+
+        // Without an intersection the closest pair always includes an end point of
+        // the segment or a corner of the box.
+        let point_to_box = |p: Vec2| (p.abs() - half_size).max(Vec2::ZERO).length();
+        let corners = [
+            Vec2::new(-half_size.x, -half_size.y),
+            Vec2::new(half_size.x, -half_size.y),
+            Vec2::new(half_size.x, half_size.y),
+            Vec2::new(-half_size.x, half_size.y),
+        ];
+
+        corners
+            .iter()
+            .map(|corner| point_segment_distance(*corner, a, b))
+            .fold(point_to_box(a).min(point_to_box(b)), f32::min)
+    }
+}
+
+/// Whether the segment `a`-`b` crosses the box centred on the origin (slab clipping).
+fn segment_hits_box(a: Vec2, b: Vec2, half_size: Vec2) -> bool {
+    let d = b - a;
+    let (mut enter, mut exit) = (0.0_f32, 1.0_f32);
+
+    for i in 0..2 {
+        if d[i].abs() < f32::EPSILON {
+            if a[i].abs() > half_size[i] {
+                return false;
+            }
+            continue;
+        }
+
+        let t1 = (-half_size[i] - a[i]) / d[i];
+        let t2 = (half_size[i] - a[i]) / d[i];
+        enter = enter.max(t1.min(t2));
+        exit = exit.min(t1.max(t2));
+        if enter > exit {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn point_segment_distance(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= f32::EPSILON {
+        return p.distance(a);
+    }
+
+    let t = ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+fn segment_segment_distance(a1: Vec2, b1: Vec2, a2: Vec2, b2: Vec2) -> f32 {
+    let d1 = b1 - a1;
+    let d2 = b2 - a2;
+
+    // Proper crossing: each segment's end points lie on opposite sides of the other.
+    let side = |origin: Vec2, dir: Vec2, p: Vec2| dir.perp_dot(p - origin);
+    let crosses =
+        side(a1, d1, a2) * side(a1, d1, b2) < 0.0 && side(a2, d2, a1) * side(a2, d2, b1) < 0.0;
+    if crosses {
+        return 0.0;
+    }
+
+    point_segment_distance(a1, a2, b2)
+        .min(point_segment_distance(b1, a2, b2))
+        .min(point_segment_distance(a2, a1, b1))
+        .min(point_segment_distance(b2, a1, b1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn world(shape: ColliderShape, transform: Transform) -> WorldShape {
+        let collider = Collider {
+            shape,
+            layer: CollisionLayers::ENEMY,
+            mask: CollisionLayers::PLAYER,
+        };
+        collider.world_shape(&transform)
+    }
+
+    /// Checks both argument orders agree before returning the result.
+    fn overlaps(a: WorldShape, b: WorldShape) -> bool {
+        let result = a.overlaps(&b);
+        assert_eq!(result, b.overlaps(&a), "overlaps is not symmetric");
+        result
+    }
+
+    fn at(x: f32, y: f32) -> Transform {
+        Transform::from_xyz(x, y, 0.0)
+    }
+
+    fn circle(radius: f32) -> ColliderShape {
+        ColliderShape::Circle { radius }
+    }
+
+    fn rect(width: f32, height: f32) -> ColliderShape {
+        ColliderShape::Rectangle {
+            size: Vec2::new(width, height),
+        }
+    }
+
+    fn capsule(width: f32, height: f32) -> ColliderShape {
+        ColliderShape::Capsule {
+            size: Vec2::new(width, height),
+        }
+    }
+
+    fn segment(length: f32, width: f32) -> ColliderShape {
+        ColliderShape::Segment { length, width }
+    }
+
+    #[test]
+    fn circle_circle() {
+        let a = world(circle(5.0), at(0.0, 0.0));
+        assert!(overlaps(a, world(circle(5.0), at(9.0, 0.0))));
+        assert!(!overlaps(a, world(circle(5.0), at(11.0, 0.0))));
+
+        // Scaled by 2 the radius becomes 10, so the pair reaches 15.
+        let scaled = |x| world(circle(5.0), at(x, 0.0).with_scale(Vec3::splat(2.0)));
+        assert!(overlaps(a, scaled(14.0)));
+        assert!(!overlaps(a, scaled(16.0)));
+    }
+
+    #[test]
+    fn circle_rect() {
+        let r = world(rect(20.0, 10.0), at(0.0, 0.0));
+        assert!(overlaps(r, world(circle(5.0), at(14.0, 0.0))));
+        assert!(!overlaps(r, world(circle(5.0), at(16.0, 0.0))));
+        // Near the corner (10, 5), but only the rounded distance counts.
+        assert!(overlaps(r, world(circle(5.0), at(13.0, 8.0))));
+        assert!(!overlaps(r, world(circle(5.0), at(14.0, 9.0))));
+
+        // Turned a quarter the box is 10 wide and 20 tall.
+        let turned = world(
+            rect(20.0, 10.0),
+            at(0.0, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+        );
+        assert!(!overlaps(turned, world(circle(5.0), at(14.0, 0.0))));
+        assert!(overlaps(turned, world(circle(5.0), at(0.0, 14.0))));
+    }
+
+    #[test]
+    fn circle_capsule() {
+        // Radius 5 around the segment from (0, -10) to (0, 10).
+        let c = world(capsule(10.0, 30.0), at(0.0, 0.0));
+        assert!(overlaps(c, world(circle(5.0), at(0.0, 19.0))));
+        assert!(!overlaps(c, world(circle(5.0), at(0.0, 21.0))));
+        assert!(overlaps(c, world(circle(5.0), at(9.0, 0.0))));
+        assert!(!overlaps(c, world(circle(5.0), at(11.0, 0.0))));
+    }
+
+    #[test]
+    fn rect_rect() {
+        let r = world(rect(20.0, 10.0), at(0.0, 0.0));
+        assert!(overlaps(r, world(rect(20.0, 10.0), at(19.0, 0.0))));
+        assert!(!overlaps(r, world(rect(20.0, 10.0), at(21.0, 0.0))));
+
+        // A 10x10 box turned 45 degrees reaches 7.07 from its centre.
+        let square = world(rect(10.0, 10.0), at(0.0, 0.0));
+        let diamond = |x| {
+            world(
+                rect(10.0, 10.0),
+                at(x, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+            )
+        };
+        assert!(overlaps(square, diamond(11.5)));
+        assert!(!overlaps(square, diamond(12.5)));
+
+        // A thin diagonal bar: its bounding box covers (10, -10) but the bar does not.
+        let bar = world(
+            rect(40.0, 4.0),
+            at(0.0, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+        );
+        assert!(!overlaps(bar, world(rect(4.0, 4.0), at(10.0, -10.0))));
+        assert!(overlaps(bar, world(rect(4.0, 4.0), at(10.0, 10.0))));
+    }
+
+    #[test]
+    fn rect_capsule() {
+        let r = world(rect(20.0, 10.0), at(0.0, 0.0));
+        // The capsule's lower end sits 10 below its centre.
+        assert!(overlaps(r, world(capsule(10.0, 30.0), at(0.0, 19.0))));
+        assert!(!overlaps(r, world(capsule(10.0, 30.0), at(0.0, 21.0))));
+
+        // Turned a quarter the capsule lies along x, ending 10 left of its centre.
+        let lying = |x| {
+            world(
+                capsule(10.0, 30.0),
+                at(x, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+            )
+        };
+        assert!(overlaps(r, lying(24.0)));
+        assert!(!overlaps(r, lying(26.0)));
+    }
+
+    #[test]
+    fn capsule_capsule() {
+        let c = world(capsule(10.0, 30.0), at(0.0, 0.0));
+        assert!(overlaps(c, world(capsule(10.0, 30.0), at(9.0, 0.0))));
+        assert!(!overlaps(c, world(capsule(10.0, 30.0), at(11.0, 0.0))));
+
+        // Crossing segments touch even though no end point is close.
+        let lying = world(
+            capsule(2.0, 40.0),
+            at(0.0, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_2)),
+        );
+        assert!(overlaps(world(capsule(2.0, 40.0), at(0.0, 0.0)), lying));
+
+        // Scaled by 2 the radius becomes 10, so the pair reaches 15 side to side.
+        let scaled = |x| world(capsule(10.0, 30.0), at(x, 0.0).with_scale(Vec3::splat(2.0)));
+        assert!(overlaps(c, scaled(14.0)));
+        assert!(!overlaps(c, scaled(16.0)));
+    }
+
+    #[test]
+    fn segment_rect() {
+        // Turned 45 degrees the box reaches 15 * cos 45 (about 10.6) to either side.
+        let r = world(
+            rect(20.0, 10.0),
+            at(0.0, 0.0).with_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+        );
+        let beam = |x, length| world(segment(length, 2.0), at(x, -50.0));
+        assert!(overlaps(r, beam(11.0, 100.0)));
+        assert!(!overlaps(r, beam(12.5, 100.0)));
+
+        // Straight through the middle, with both ends and every corner far away.
+        assert!(overlaps(r, beam(0.0, 100.0)));
+        // The lower edge crosses x = 0 at about y = -7.1.
+        assert!(!overlaps(r, beam(0.0, 38.0)));
+        assert!(overlaps(r, beam(0.0, 44.0)));
+
+        // Scaled by 2 along y the beam is twice as long.
+        let stretched = world(
+            segment(38.0, 2.0),
+            at(0.0, -50.0).with_scale(Vec3::new(1.0, 2.0, 1.0)),
+        );
+        assert!(overlaps(r, stretched));
+    }
+
+    #[test]
+    fn segment_hits_box_clips_both_slabs() {
+        let half = Vec2::new(5.0, 5.0);
+        assert!(segment_hits_box(
+            Vec2::new(-20.0, 0.0),
+            Vec2::new(20.0, 0.0),
+            half
+        ));
+        assert!(segment_hits_box(
+            Vec2::new(-20.0, -20.0),
+            Vec2::new(20.0, 20.0),
+            half
+        ));
+        // Parallel to an axis but outside the slab.
+        assert!(!segment_hits_box(
+            Vec2::new(-20.0, 6.0),
+            Vec2::new(20.0, 6.0),
+            half
+        ));
+        // Aimed at the box but stopping short of it.
+        assert!(!segment_hits_box(
+            Vec2::new(-20.0, 0.0),
+            Vec2::new(-6.0, 0.0),
+            half
+        ));
+        // Passing the corner diagonally.
+        assert!(!segment_hits_box(
+            Vec2::new(0.0, 12.0),
+            Vec2::new(12.0, 0.0),
+            half
+        ));
+    }
 }
@@ -1,7 +1,13 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
 
-use super::{assets::AssetVisual, bullet::BulletDefinition};
+use super::{
+    assets::{AssetVisual, VisualAsset},
+    bullet::{BulletAsset, BulletDefinition, BulletPattern},
+    path::MovePath,
+};
 
 #[derive(Component)]
 pub struct Enemy;
@@ -15,6 +21,7 @@ pub struct EnemyDefinition {
     pub fire_interval: f32,
 
     pub bullet: Option<BulletDefinition>,
+    pub bullet_pattern: BulletPattern,
     pub visual: AssetVisual,
 }
 
@@ -23,16 +30,95 @@ pub struct EnemyDatabase {
     pub defs: HashMap<EnemyKind, EnemyDefinition>,
 }
 
+/// Enemy definition loaded from `assets/enemies/*.enemy.ron`.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct EnemyAsset {
+    pub id: String,
+    pub max_hp: u32,
+    pub movement_pattern: EnemyMovePattern,
+    pub collider_size: (f32, f32),
+    pub fire_interval: f32,
+
+    pub bullet: Option<BulletAsset>,
+    #[serde(default)]
+    pub bullet_pattern: BulletPattern,
+    pub visual: VisualAsset,
+}
+
+#[derive(Debug)]
+pub enum EnemyLoadError {
+    InvalidFireInterval { id: String, value: f32 },
+    InvalidBurstDelay { id: String, value: f32 },
+}
+
+impl fmt::Display for EnemyLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EnemyLoadError::InvalidFireInterval { id, value } => write!(
+                f,
+                "enemy \"{}\": fire_interval must be positive, got {}",
+                id, value
+            ),
+            EnemyLoadError::InvalidBurstDelay { id, value } => write!(
+                f,
+                "enemy \"{}\": burst delay must be positive, got {}",
+                id, value
+            ),
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct EnemySpawnTimer {
     pub timer: Timer,
 }
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone, Debug, PartialEq, Deserialize)]
 pub enum EnemyMovePattern {
     Straight,
     Zigzag,
     Homing,
+    /// Follows the path from the spawn position, then falls straight down once the path
+    /// ends.
+    Path(MovePath),
+}
+
+/// Per-enemy movement clock, so enemies spawned at different times move independently.
+#[derive(Component)]
+pub struct EnemyMoveState {
+    pub elapsed: f32,
+    pub origin: Vec2,
+}
+
+#[derive(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+/// Brightens the sprite until the timer runs out.
+#[derive(Component)]
+pub struct HitFlash {
+    pub timer: Timer,
+}
+
+impl HitFlash {
+    pub const DURATION: f32 = 0.08;
+}
+
+impl Default for HitFlash {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(Self::DURATION, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct EnemyDefeated {
+    pub position: Vec3,
+    /// Leader of the formation the enemy belonged to, if any.
+    pub formation: Option<Entity>,
 }
 
 #[derive(Component)]
@@ -40,9 +126,6 @@ pub struct EnemyFireTimer {
     pub timer: Timer,
 }
 
+/// Id of an enemy definition, matching `EnemyAsset::id`.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
-pub enum EnemyKind {
-    DinoStraight,
-    DinoZigzag,
-    DinoHoming,
-}
+pub struct EnemyKind(pub String);
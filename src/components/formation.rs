@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Group of enemies spawned together. Destroying every member awards `bonus` points and
+/// drops an item.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct FormationSetting {
+    pub shape: FormationShape,
+    #[serde(default = "default_bonus")]
+    pub bonus: u32,
+}
+
+fn default_bonus() -> u32 {
+    500
+}
+
+/// Arrangement of the members around the leader. Distances are in pixels.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FormationShape {
+    Line {
+        count: u32,
+        spacing: f32,
+    },
+    V {
+        count: u32,
+        spacing: f32,
+    },
+    Circle {
+        count: u32,
+        radius: f32,
+    },
+    Grid {
+        columns: u32,
+        rows: u32,
+        spacing: f32,
+    },
+}
+
+/// Invisible entity that moves along the formation's path. Members keep a fixed offset
+/// from it.
+#[derive(Component)]
+pub struct FormationLeader {
+    pub bonus: u32,
+    pub escaped: bool,
+    pub last_position: Vec2,
+}
+
+/// Sent when every member of a formation has been destroyed.
+#[derive(Event)]
+pub struct FormationCleared {
+    /// Where the last member went down.
+    pub position: Vec3,
+    pub bonus: u32,
+}
+
+#[derive(Component)]
+pub struct FormationMember {
+    pub leader: Entity,
+    pub offset: Vec2,
+}
+
+impl FormationShape {
+    /// Offsets of each member from the leader position.
+    pub fn offsets(&self) -> Vec<Vec2> {
+        match *self {
+            FormationShape::Line { count, spacing } => {
+                let width = spacing * count.saturating_sub(1) as f32;
+                (0..count)
+                    .map(|i| Vec2::new(i as f32 * spacing - width / 2.0, 0.0))
+                    .collect()
+            }
+            FormationShape::V { count, spacing } => (0..count)
+                .map(|i| {
+                    let rank = i.div_ceil(2) as f32;
+                    let side = if i % 2 == 1 { -1.0 } else { 1.0 };
+                    Vec2::new(side * rank * spacing, rank * spacing)
+                })
+                .collect(),
+            FormationShape::Circle { count, radius } => {
+                let step = std::f32::consts::TAU / count.max(1) as f32;
+                (0..count)
+                    .map(|i| Vec2::from_angle(step * i as f32) * radius)
+                    .collect()
+            }
+            FormationShape::Grid {
+                columns,
+                rows,
+                spacing,
+            } => {
+                let width = spacing * columns.saturating_sub(1) as f32;
+                (0..rows)
+                    .flat_map(|row| {
+                        (0..columns).map(move |column| {
+                            Vec2::new(column as f32 * spacing - width / 2.0, row as f32 * spacing)
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Focus,
+    Bomb,
+    Parry,
+    Confirm,
+    Back,
+    Pause,
+    Restart,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Focus,
+        Action::Bomb,
+        Action::Parry,
+        Action::Confirm,
+        Action::Back,
+        Action::Pause,
+        Action::Restart,
+    ];
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyCode),
+    Button(GamepadButton),
+}
+
+impl Binding {
+    fn same_device(&self, other: &Binding) -> bool {
+        matches!(
+            (self, other),
+            (Binding::Key(_), Binding::Key(_)) | (Binding::Button(_), Binding::Button(_))
+        )
+    }
+}
+
+/// Keyboard and gamepad bindings for every action, read from and saved to
+/// `config/input.ron`. The left stick always moves the player in addition to the
+/// move bindings.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct InputMap {
+    pub bindings: BTreeMap<Action, Vec<Binding>>,
+    #[serde(default = "default_stick_deadzone")]
+    pub stick_deadzone: f32,
+}
+
+fn default_stick_deadzone() -> f32 {
+    0.2
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use Binding::*;
+
+        let bindings = BTreeMap::from([
+            (
+                Action::MoveUp,
+                vec![
+                    Key(KeyCode::ArrowUp),
+                    Key(KeyCode::KeyW),
+                    Key(KeyCode::KeyK),
+                    Button(GamepadButton::DPadUp),
+                ],
+            ),
+            (
+                Action::MoveDown,
+                vec![
+                    Key(KeyCode::ArrowDown),
+                    Key(KeyCode::KeyS),
+                    Key(KeyCode::KeyJ),
+                    Button(GamepadButton::DPadDown),
+                ],
+            ),
+            (
+                Action::MoveLeft,
+                vec![
+                    Key(KeyCode::ArrowLeft),
+                    Key(KeyCode::KeyA),
+                    Key(KeyCode::KeyH),
+                    Button(GamepadButton::DPadLeft),
+                ],
+            ),
+            (
+                Action::MoveRight,
+                vec![
+                    Key(KeyCode::ArrowRight),
+                    Key(KeyCode::KeyD),
+                    Key(KeyCode::KeyL),
+                    Button(GamepadButton::DPadRight),
+                ],
+            ),
+            (
+                Action::Fire,
+                vec![Key(KeyCode::Space), Button(GamepadButton::South)],
+            ),
+            (
+                Action::Focus,
+                vec![
+                    Key(KeyCode::ShiftLeft),
+                    Key(KeyCode::ShiftRight),
+                    Button(GamepadButton::RightTrigger),
+                ],
+            ),
+            (
+                Action::Bomb,
+                vec![Key(KeyCode::KeyX), Button(GamepadButton::East)],
+            ),
+            (
+                Action::Parry,
+                vec![Key(KeyCode::KeyC), Button(GamepadButton::West)],
+            ),
+            (
+                Action::Confirm,
+                vec![
+                    Key(KeyCode::Space),
+                    Key(KeyCode::Enter),
+                    Button(GamepadButton::South),
+                ],
+            ),
+            (
+                Action::Back,
+                vec![Key(KeyCode::Backspace), Button(GamepadButton::East)],
+            ),
+            (
+                Action::Pause,
+                vec![Key(KeyCode::Escape), Button(GamepadButton::Start)],
+            ),
+            // Kept off Fire's keys so a player still shooting when they die does not
+            // restart straight away.
+            (
+                Action::Restart,
+                vec![Key(KeyCode::KeyR), Button(GamepadButton::Select)],
+            ),
+        ]);
+
+        Self {
+            bindings,
+            stick_deadzone: default_stick_deadzone(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        match self.bindings.get(&action) {
+            Some(bindings) => bindings,
+            None => &[],
+        }
+    }
+
+    /// Replaces the action's first binding on the same device (keyboard or gamepad) with
+    /// `binding`. Alternate bindings and the other device are left alone.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let bindings = self.bindings.entry(action).or_default();
+        if bindings.contains(&binding) {
+            return;
+        }
+
+        match bindings
+            .iter_mut()
+            .find(|existing| existing.same_device(&binding))
+        {
+            Some(existing) => *existing = binding,
+            None => bindings.push(binding),
+        }
+    }
+}
+
+/// Set while waiting for the next key or button to bind to `action`.
+#[derive(Resource, Default)]
+pub struct RebindRequest {
+    pub action: Option<Action>,
+    /// Skips the frame the request was made in, so the press that started it is not
+    /// captured as the new binding.
+    pub armed: bool,
+}
+
+impl RebindRequest {
+    pub fn start(&mut self, action: Action) {
+        self.action = Some(action);
+        self.armed = false;
+    }
+}
+
+/// Controls screen listing every action, with a final row to restore the defaults.
+#[derive(Component)]
+pub struct ControlsPanel {
+    pub selected: usize,
+}
+
+#[derive(Component)]
+pub struct ControlsText;
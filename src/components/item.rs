@@ -1,8 +1,13 @@
+use crate::components::weapon::WeaponKind;
 use bevy::prelude::*;
 
 #[derive(Component, Clone, Copy)]
 pub enum ItemType {
-    RapidFire,
+    /// Raises the weapon level, or shortens the fire interval once it is maxed.
+    PowerUp,
     PiercingShot,
     Heal,
+    Bomb,
+    /// Switches to this weapon, or powers it up if it is already equipped.
+    Weapon(WeaponKind),
 }
@@ -1,11 +1,18 @@
 pub mod animation;
 pub mod assets;
+pub mod boss;
+pub mod broadphase;
 pub mod bullet;
 pub mod collider;
 pub mod enemy;
 pub mod explosion;
+pub mod formation;
+pub mod input;
 pub mod item;
+pub mod path;
 pub mod player;
+pub mod playfield;
 pub mod score;
 pub mod stage;
 pub mod wave;
+pub mod weapon;
@@ -0,0 +1,278 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Movement made of consecutive segments. Points are relative to the spawn position and
+/// each segment starts where the previous one ended. Speeds are in pixels per second.
+/// A looping path repeats `max_loops` times before it ends, so it cannot hold a wave open
+/// forever.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(from = "MovePathData")]
+pub struct MovePath {
+    pub segments: Vec<PathSegment>,
+    pub looping: bool,
+    pub max_loops: u32,
+    /// Start point, duration and curve points of every segment, worked out once when the
+    /// path is built.
+    spans: Vec<PathSpan>,
+    total: f32,
+}
+
+/// `MovePath` as written in data files.
+#[derive(Deserialize)]
+struct MovePathData {
+    segments: Vec<PathSegment>,
+    #[serde(default)]
+    looping: bool,
+    #[serde(default = "default_max_loops")]
+    max_loops: u32,
+}
+
+fn default_max_loops() -> u32 {
+    3
+}
+
+impl From<MovePathData> for MovePath {
+    fn from(data: MovePathData) -> Self {
+        MovePath::new(data.segments, data.looping, data.max_loops)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct PathSpan {
+    start: Vec2,
+    duration: f32,
+    /// The segment's points with its start prepended, for waypoints and splines.
+    points: Vec<Vec2>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    Line {
+        to: (f32, f32),
+        speed: f32,
+        #[serde(default)]
+        easing: Easing,
+    },
+    Waypoints {
+        points: Vec<(f32, f32)>,
+        speed: f32,
+        #[serde(default)]
+        easing: Easing,
+    },
+    Bezier {
+        control1: (f32, f32),
+        control2: (f32, f32),
+        to: (f32, f32),
+        speed: f32,
+        #[serde(default)]
+        easing: Easing,
+    },
+    CatmullRom {
+        points: Vec<(f32, f32)>,
+        speed: f32,
+        #[serde(default)]
+        easing: Easing,
+    },
+    Hover {
+        duration: f32,
+    },
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+const CURVE_SAMPLES: usize = 20;
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+impl MovePath {
+    pub fn new(segments: Vec<PathSegment>, looping: bool, max_loops: u32) -> Self {
+        let mut start = Vec2::ZERO;
+        let spans: Vec<PathSpan> = segments
+            .iter()
+            .map(|segment| {
+                let points = segment.points(start);
+                let duration = segment.duration(start, &points);
+                let span = PathSpan {
+                    start,
+                    duration,
+                    points,
+                };
+                start = segment.point(start, &span.points, 1.0);
+                span
+            })
+            .collect();
+        let total = spans.iter().map(|span| span.duration).sum();
+
+        Self {
+            segments,
+            looping,
+            max_loops,
+            spans,
+            total,
+        }
+    }
+
+    /// Offset from the spawn position after `elapsed` seconds, or `None` once the path
+    /// has been walked to the end (`max_loops` times for a looping path).
+    pub fn offset_at(&self, elapsed: f32) -> Option<Vec2> {
+        if self.total <= 0.0 {
+            return None;
+        }
+
+        let mut time = elapsed;
+        let loops = if self.looping {
+            self.max_loops.max(1)
+        } else {
+            1
+        };
+        if time >= self.total * loops as f32 {
+            return None;
+        }
+        time %= self.total;
+
+        for (segment, span) in self.segments.iter().zip(&self.spans) {
+            if time < span.duration {
+                return Some(segment.point(span.start, &span.points, time / span.duration));
+            }
+            time -= span.duration;
+        }
+
+        self.spans
+            .last()
+            .zip(self.segments.last())
+            .map(|(span, segment)| segment.point(span.start, &span.points, 1.0))
+    }
+}
+
+impl PathSegment {
+    fn duration(&self, start: Vec2, points: &[Vec2]) -> f32 {
+        let (speed, length) = match self {
+            PathSegment::Hover { duration } => return duration.max(0.0),
+            PathSegment::Line { speed, .. }
+            | PathSegment::Waypoints { speed, .. }
+            | PathSegment::Bezier { speed, .. }
+            | PathSegment::CatmullRom { speed, .. } => (*speed, self.length(start, points)),
+        };
+
+        if speed <= 0.0 { 0.0 } else { length / speed }
+    }
+
+    fn length(&self, start: Vec2, points: &[Vec2]) -> f32 {
+        let mut length = 0.0;
+        let mut previous = start;
+        for i in 1..=CURVE_SAMPLES {
+            let point = self.curve_point(start, points, i as f32 / CURVE_SAMPLES as f32);
+            length += previous.distance(point);
+            previous = point;
+        }
+        length
+    }
+
+    /// The points of a waypoint or spline segment with `start` prepended, empty for
+    /// the other kinds.
+    fn points(&self, start: Vec2) -> Vec<Vec2> {
+        match self {
+            PathSegment::Waypoints { points, .. } | PathSegment::CatmullRom { points, .. } => {
+                std::iter::once(start)
+                    .chain(points.iter().map(|p| Vec2::from(*p)))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Position along the segment at `t` in `0.0..=1.0`, easing applied.
+    fn point(&self, start: Vec2, points: &[Vec2], t: f32) -> Vec2 {
+        let eased = match self {
+            PathSegment::Line { easing, .. }
+            | PathSegment::Waypoints { easing, .. }
+            | PathSegment::Bezier { easing, .. }
+            | PathSegment::CatmullRom { easing, .. } => easing.apply(t),
+            PathSegment::Hover { .. } => t,
+        };
+        self.curve_point(start, points, eased)
+    }
+
+    fn curve_point(&self, start: Vec2, points: &[Vec2], t: f32) -> Vec2 {
+        match self {
+            PathSegment::Hover { .. } => start,
+            PathSegment::Line { to, .. } => start.lerp(Vec2::from(*to), t),
+            PathSegment::Waypoints { .. } => polyline_point(points, t),
+            PathSegment::Bezier {
+                control1,
+                control2,
+                to,
+                ..
+            } => {
+                let (p1, p2, p3) = (
+                    Vec2::from(*control1),
+                    Vec2::from(*control2),
+                    Vec2::from(*to),
+                );
+                let u = 1.0 - t;
+                start * u * u * u + p1 * 3.0 * u * u * t + p2 * 3.0 * u * t * t + p3 * t * t * t
+            }
+            PathSegment::CatmullRom { .. } => catmull_rom_point(points, t),
+        }
+    }
+}
+
+/// Point at `t` of the polyline through `points`, parametrised by distance so the speed
+/// stays constant across waypoints.
+fn polyline_point(points: &[Vec2], t: f32) -> Vec2 {
+    let total: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+    if total <= 0.0 {
+        return points[points.len() - 1];
+    }
+
+    let mut remaining = t.clamp(0.0, 1.0) * total;
+    for w in points.windows(2) {
+        let length = w[0].distance(w[1]);
+        if remaining <= length && length > 0.0 {
+            return w[0].lerp(w[1], remaining / length);
+        }
+        remaining -= length;
+    }
+
+    points[points.len() - 1]
+}
+
+/// Point at `t` of the Catmull-Rom spline passing through every point.
+fn catmull_rom_point(points: &[Vec2], t: f32) -> Vec2 {
+    let spans = points.len() - 1;
+    if spans == 0 {
+        return points[0];
+    }
+
+    let scaled = t.clamp(0.0, 1.0) * spans as f32;
+    let i = (scaled.floor() as usize).min(spans - 1);
+    let local = scaled - i as f32;
+
+    let p0 = points[i.saturating_sub(1)];
+    let p1 = points[i];
+    let p2 = points[i + 1];
+    let p3 = points[(i + 2).min(spans)];
+
+    let t2 = local * local;
+    let t3 = t2 * local;
+    0.5 * ((2.0 * p1)
+        + (p2 - p0) * local
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+}
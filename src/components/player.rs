@@ -1,3 +1,4 @@
+use crate::components::weapon::Weapon;
 use bevy::prelude::*;
 
 #[derive(Component)]
@@ -6,12 +7,100 @@ pub struct Player {
     pub hp: i32,
     pub invincible_timer: Timer,
     pub shoot_interval: f32,
+    pub bullet_damage: u32,
     pub piercing: bool,
+    pub focused: bool,
+    pub speed: f32,
+    pub focus_speed: f32,
+    pub weapon: Weapon,
+    pub bombs: u32,
+    pub max_bombs: u32,
+}
+
+/// The small hitbox enemies and their bullets actually hit. It follows the player and
+/// shows as a dot while focused when `visible` is set.
+#[derive(Component)]
+pub struct PlayerCore {
+    pub player: Entity,
+    pub visible: bool,
 }
 
 #[derive(Component)]
 pub struct Heart;
 
+#[derive(Component)]
+pub struct BombText;
+
+/// Full-screen white flash that fades out after a bomb goes off.
+#[derive(Component)]
+pub struct BombFlash {
+    pub timer: Timer,
+}
+
+/// Enemy bullets touching the player while `window` runs are reflected. A new window
+/// can only open once `cooldown` has finished.
+#[derive(Component)]
+pub struct Parry {
+    pub window: Timer,
+    pub cooldown: Timer,
+}
+
+impl Parry {
+    pub const WINDOW: f32 = 0.2;
+    pub const COOLDOWN: f32 = 1.0;
+
+    pub fn is_active(&self) -> bool {
+        !self.window.finished()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown.finished()
+    }
+
+    pub fn start(&mut self) {
+        self.window.reset();
+        self.cooldown.reset();
+    }
+}
+
+impl Default for Parry {
+    /// Closed and ready to use.
+    fn default() -> Self {
+        let finished = |secs: f32| {
+            let mut timer = Timer::from_seconds(secs, TimerMode::Once);
+            timer.tick(timer.duration());
+            timer
+        };
+        Self {
+            window: finished(Self::WINDOW),
+            cooldown: finished(Self::COOLDOWN),
+        }
+    }
+}
+
+/// Ring around the player while a parry window is open.
+#[derive(Component)]
+pub struct ParryRing;
+
+#[derive(Event)]
+pub struct BulletParried;
+
+#[derive(Event)]
+pub struct PlayerDamaged {
+    pub position: Vec3,
+    pub killed: bool,
+}
+
+impl Player {
+    pub fn current_speed(&self) -> f32 {
+        if self.focused {
+            self.focus_speed
+        } else {
+            self.speed
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct DeadTimer {
     pub timer: Timer,
@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+/// Half the size of the playfield. It fills the window and is centred on the origin.
+pub const HALF_SIZE: Vec2 = Vec2::new(240.0, 360.0);
+
+pub fn contains(position: Vec2) -> bool {
+    position.x.abs() <= HALF_SIZE.x && position.y.abs() <= HALF_SIZE.y
+}
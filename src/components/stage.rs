@@ -1,6 +1,9 @@
+use crate::components::boss::BossSetting;
 use crate::components::wave::*;
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
 pub enum Stage {
@@ -17,7 +20,21 @@ pub struct StageSetting {
     pub name: String,
     pub background_image: Handle<Image>,
     pub waves: Vec<Wave>,
+    pub boss: Option<BossSetting>,
     pub current_index: usize,
+    pub source: AssetId<StageAsset>,
+}
+
+impl StageSetting {
+    /// Rewinds the stage to its first wave so it can be played again.
+    pub fn reset_progress(&mut self) {
+        self.current_index = 0;
+        for wave in self.waves.iter_mut() {
+            wave.defeated_count = 0;
+            wave.elapsed = 0.0;
+            wave.next_event = 0;
+        }
+    }
 }
 
 #[derive(Resource)]
@@ -28,3 +45,112 @@ pub struct StageDatabase {
 
 #[derive(Component)]
 pub struct BackgroundImage;
+
+/// Stage definition loaded from `assets/stages/*.stage.ron`.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct StageAsset {
+    pub name: String,
+    pub background: String,
+    pub waves: Vec<WaveAsset>,
+    #[serde(default)]
+    pub boss: Option<BossSetting>,
+}
+
+#[derive(Debug)]
+pub enum StageLoadError {
+    NoWaves {
+        stage: String,
+    },
+    UnknownEnemyKind {
+        stage: String,
+        wave: usize,
+        kind: String,
+    },
+    EmptyDistribution {
+        stage: String,
+        wave: usize,
+    },
+    EmptyTimeline {
+        stage: String,
+        wave: usize,
+    },
+    EmptyFormation {
+        stage: String,
+        wave: usize,
+    },
+    UnknownBossEnemy {
+        stage: String,
+        kind: String,
+    },
+    BossWithoutPhases {
+        stage: String,
+    },
+    InvalidBossFireInterval {
+        stage: String,
+        phase: usize,
+        value: f32,
+    },
+    InvalidBossBurstDelay {
+        stage: String,
+        phase: usize,
+        value: f32,
+    },
+}
+
+impl fmt::Display for StageLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StageLoadError::NoWaves { stage } => {
+                write!(f, "stage \"{}\" has no waves", stage)
+            }
+            StageLoadError::UnknownEnemyKind { stage, wave, kind } => write!(
+                f,
+                "stage \"{}\" wave {}: unknown enemy kind \"{}\"",
+                stage, wave, kind
+            ),
+            StageLoadError::EmptyDistribution { stage, wave } => write!(
+                f,
+                "stage \"{}\" wave {}: enemy_distribution has no positive weight",
+                stage, wave
+            ),
+            StageLoadError::EmptyTimeline { stage, wave } => {
+                write!(
+                    f,
+                    "stage \"{}\" wave {}: timeline has no events",
+                    stage, wave
+                )
+            }
+            StageLoadError::EmptyFormation { stage, wave } => write!(
+                f,
+                "stage \"{}\" wave {}: formation has no members",
+                stage, wave
+            ),
+            StageLoadError::UnknownBossEnemy { stage, kind } => write!(
+                f,
+                "stage \"{}\" boss: unknown enemy kind \"{}\"",
+                stage, kind
+            ),
+            StageLoadError::BossWithoutPhases { stage } => {
+                write!(f, "stage \"{}\" boss has no phases", stage)
+            }
+            StageLoadError::InvalidBossFireInterval {
+                stage,
+                phase,
+                value,
+            } => write!(
+                f,
+                "stage \"{}\" boss phase {}: fire_interval must be positive, got {}",
+                stage, phase, value
+            ),
+            StageLoadError::InvalidBossBurstDelay {
+                stage,
+                phase,
+                value,
+            } => write!(
+                f,
+                "stage \"{}\" boss phase {}: burst delay must be positive, got {}",
+                stage, phase, value
+            ),
+        }
+    }
+}
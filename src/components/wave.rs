@@ -1,5 +1,7 @@
-use crate::components::enemy::EnemyKind;
+use crate::components::enemy::{EnemyKind, EnemyMovePattern};
+use crate::components::formation::FormationSetting;
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
@@ -10,6 +12,70 @@ pub struct Wave {
     pub enemy_speed: f32,
     pub spawn_interval: f32,
     pub enemy_distribution: HashMap<EnemyKind, f32>,
+    /// Chance in `0.0..=1.0` that a random spawn is one of `formations` instead of a
+    /// single enemy.
+    pub formation_rate: f32,
+    pub formations: Vec<FormationSetting>,
+    pub mode: WaveMode,
+    pub timeline: Vec<SpawnEvent>,
+    /// Seconds since a timeline wave started, and the next event to spawn.
+    pub elapsed: f32,
+    pub next_event: usize,
+}
+
+/// `Random` waves spawn from `enemy_distribution` every `spawn_interval` until
+/// `target_count` enemies are defeated. `Timeline` waves play `timeline` once and end
+/// when every spawned enemy is gone.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum WaveMode {
+    #[default]
+    Random,
+    Timeline,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpawnEvent {
+    pub time: f32,
+    pub enemy: EnemyKind,
+    pub position: Vec2,
+    pub movement: Option<EnemyMovePattern>,
+    pub formation: Option<FormationSetting>,
+}
+
+/// Wave as written in a `*.stage.ron` file. Enemy kinds are referenced by name.
+#[derive(Deserialize, Clone, Debug)]
+pub struct WaveAsset {
+    #[serde(default)]
+    pub mode: WaveMode,
+    #[serde(default)]
+    pub target_count: u32,
+    pub enemy_speed: f32,
+    #[serde(default = "default_spawn_interval")]
+    pub spawn_interval: f32,
+    #[serde(default)]
+    pub enemy_distribution: HashMap<String, f32>,
+    #[serde(default)]
+    pub formation_rate: f32,
+    #[serde(default)]
+    pub formations: Vec<FormationSetting>,
+    #[serde(default)]
+    pub timeline: Vec<SpawnEventAsset>,
+}
+
+fn default_spawn_interval() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct SpawnEventAsset {
+    pub time: f32,
+    pub enemy: String,
+    pub position: (f32, f32),
+    /// Overrides the movement of the enemy definition.
+    #[serde(default)]
+    pub movement: Option<EnemyMovePattern>,
+    #[serde(default)]
+    pub formation: Option<FormationSetting>,
 }
 
 //#[derive(Resource, Clone, Debug)]
@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+
+/// The player's main gun. Power-up items raise `level` up to `Weapon::MAX_LEVEL`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub level: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WeaponKind {
+    /// A fan of bullets: three at level 1 and two more per level. Focused, the same
+    /// bullets fly side by side, faster.
+    Spread,
+    /// A continuous beam held on the first enemy in its path. Its width and damage per
+    /// second grow with the level. Focused, the beam is thinner and hits harder.
+    Laser,
+    /// Slow, heavy missiles launched from the wings that chase the nearest enemy, one more
+    /// pair per level. Focused, they fly straight ahead, faster and without homing.
+    Homing,
+    /// Streams of small bullets at a high rate of fire, one more stream per level.
+    /// Focused, the streams close up and speed up.
+    Gatling,
+}
+
+impl WeaponKind {
+    pub const ALL: [WeaponKind; 4] = [
+        WeaponKind::Spread,
+        WeaponKind::Laser,
+        WeaponKind::Homing,
+        WeaponKind::Gatling,
+    ];
+
+    pub fn color(&self) -> Color {
+        match self {
+            WeaponKind::Spread => Color::srgb(1.0, 1.0, 0.0),
+            WeaponKind::Laser => Color::srgb(0.3, 0.9, 1.0),
+            WeaponKind::Homing => Color::srgb(1.0, 0.5, 0.1),
+            WeaponKind::Gatling => Color::srgb(0.6, 1.0, 0.4),
+        }
+    }
+}
+
+/// One bullet of a volley, relative to the muzzle.
+pub struct Shot {
+    pub offset: Vec2,
+    pub velocity: Vec2,
+    pub size: Vec2,
+    pub damage: u32,
+    pub piercing: bool,
+    /// Steers toward the nearest enemy after launch.
+    pub homing: bool,
+}
+
+impl Weapon {
+    pub const MAX_LEVEL: u32 = 4;
+
+    pub fn new(kind: WeaponKind) -> Self {
+        Self { kind, level: 1 }
+    }
+
+    /// Returns false once the weapon is already at `MAX_LEVEL`.
+    pub fn power_up(&mut self) -> bool {
+        if self.level >= Self::MAX_LEVEL {
+            return false;
+        }
+        self.level += 1;
+        true
+    }
+
+    /// Multiplier on the player's `shoot_interval`.
+    pub fn interval_scale(&self) -> f32 {
+        match self.kind {
+            WeaponKind::Spread => 1.0,
+            WeaponKind::Laser => 1.0,
+            WeaponKind::Homing => 2.5,
+            WeaponKind::Gatling => 0.4,
+        }
+    }
+
+    /// Width and damage per second of the laser beam.
+    pub fn beam(&self, base_damage: u32, focused: bool) -> (f32, f32) {
+        let level = self.level.clamp(1, Self::MAX_LEVEL) as f32;
+        let width = 4.0 + 2.0 * level;
+        let dps = base_damage as f32 * 10.0 * (1.0 + level);
+        if focused {
+            (width / 2.0, dps * 1.5)
+        } else {
+            (width, dps)
+        }
+    }
+
+    /// The piercing shot released after holding Fire. `charge` runs from 0 to 1 and scales
+    /// both its size and damage; the weapon kind does not matter.
+    pub fn charge_shot(base_damage: u32, charge: f32) -> Shot {
+        let charge = charge.clamp(0.0, 1.0);
+        Shot {
+            offset: Vec2::ZERO,
+            velocity: Vec2::new(0.0, 700.0),
+            size: Vec2::splat(8.0 + 24.0 * charge),
+            damage: (base_damage as f32 * (2.0 + 10.0 * charge)).round() as u32,
+            piercing: true,
+            homing: false,
+        }
+    }
+
+    /// Bullets fired by one trigger pull. Every kind has its own focused volley.
+    pub fn volley(&self, base_damage: u32, focused: bool) -> Vec<Shot> {
+        let level = self.level.clamp(1, Self::MAX_LEVEL);
+
+        match self.kind {
+            WeaponKind::Spread if focused => {
+                let count = 1 + 2 * level;
+                (0..count)
+                    .map(|i| Shot {
+                        offset: Vec2::new((i as f32 - (count - 1) as f32 / 2.0) * 4.0, 0.0),
+                        velocity: Vec2::new(0.0, 800.0),
+                        size: Vec2::new(3.0, 3.0),
+                        damage: base_damage,
+                        piercing: false,
+                        homing: false,
+                    })
+                    .collect()
+            }
+            WeaponKind::Spread => {
+                let count = 1 + 2 * level;
+                (0..count)
+                    .map(|i| {
+                        let angle = (i as f32 - (count - 1) as f32 / 2.0) * 10.0;
+                        Shot {
+                            offset: Vec2::ZERO,
+                            velocity: Vec2::from_angle(angle.to_radians()).rotate(Vec2::Y) * 600.0,
+                            size: Vec2::new(3.0, 3.0),
+                            damage: base_damage,
+                            piercing: false,
+                            homing: false,
+                        }
+                    })
+                    .collect()
+            }
+            // The laser fires a `LaserBeam` instead of bullets.
+            WeaponKind::Laser => Vec::new(),
+            WeaponKind::Homing if focused => (0..level)
+                .flat_map(|pair| {
+                    let x = 6.0 + 4.0 * pair as f32;
+                    [-1.0, 1.0].map(|side| Shot {
+                        offset: Vec2::new(side * x, -10.0),
+                        velocity: Vec2::new(0.0, 500.0),
+                        size: Vec2::new(5.0, 8.0),
+                        damage: base_damage * 2,
+                        piercing: false,
+                        homing: false,
+                    })
+                })
+                .collect(),
+            WeaponKind::Homing => (0..level)
+                .flat_map(|pair| {
+                    let x = 10.0 + 8.0 * pair as f32;
+                    let angle = (10.0 + 10.0 * pair as f32).to_radians();
+                    [-1.0, 1.0].map(|side| Shot {
+                        offset: Vec2::new(side * x, -10.0),
+                        velocity: Vec2::from_angle(-side * angle).rotate(Vec2::Y) * 350.0,
+                        size: Vec2::new(5.0, 8.0),
+                        damage: base_damage * 2,
+                        piercing: false,
+                        homing: true,
+                    })
+                })
+                .collect(),
+            WeaponKind::Gatling => {
+                let (spacing, speed) = if focused { (3.0, 1100.0) } else { (8.0, 900.0) };
+                (0..level)
+                    .map(|i| Shot {
+                        offset: Vec2::new((i as f32 - (level - 1) as f32 / 2.0) * spacing, 0.0),
+                        velocity: Vec2::new(0.0, speed),
+                        size: Vec2::new(2.0, 6.0),
+                        damage: base_damage,
+                        piercing: false,
+                        homing: false,
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The laser weapon's beam. It starts at the player's muzzle and its `Segment` collider
+/// is cut short at the first enemy in the way.
+#[derive(Component, Default)]
+pub struct LaserBeam {
+    /// Whole damage points to deal this frame to every enemy the beam touches.
+    pub damage: u32,
+    /// Damage built up over frames that has not added up to a whole point yet.
+    pub carry: f32,
+}
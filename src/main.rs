@@ -1,10 +1,13 @@
 use bevy::prelude::*;
+use components::playfield;
 use states::stage::StageSelectPlugin;
 mod components;
 mod states;
 mod systems;
 use states::*;
 use states::{ClearPlugin, PlayingPlugin};
+use systems::debug::DebugPlugin;
+use systems::input::InputPlugin;
 
 #[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
 enum GameState {
@@ -13,14 +16,30 @@ enum GameState {
     StageSelect,
     Playing,
     Clear,
+    Benchmark,
+    Restart,
+}
+
+#[derive(SubStates, Debug, Clone, Eq, PartialEq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+enum PlayState {
+    #[default]
+    Running,
+    Paused,
 }
 
 fn main() {
+    let initial_state = if std::env::args().any(|arg| arg == "--benchmark") {
+        GameState::Benchmark
+    } else {
+        GameState::default()
+    };
+
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "My Vertical Shooter".into(),
-                resolution: (480., 720.).into(),
+                resolution: (playfield::HALF_SIZE * 2.0).into(),
                 resizable: false,
                 ..default()
             }),
@@ -28,12 +47,17 @@ fn main() {
         }))
         .add_plugins(bevy_kira_audio::prelude::AudioPlugin)
         .insert_resource(ClearColor(Color::BLACK))
-        .init_state::<GameState>()
+        .insert_state(initial_state)
+        .add_sub_state::<PlayState>()
         .add_systems(Startup, setup)
+        .add_plugins(InputPlugin)
         .add_plugins(TitlePlugin)
         .add_plugins(StageSelectPlugin)
         .add_plugins(PlayingPlugin)
+        .add_plugins(PausePlugin)
         .add_plugins(ClearPlugin)
+        .add_plugins(BenchmarkPlugin)
+        .add_plugins(DebugPlugin)
         .run();
 }
 
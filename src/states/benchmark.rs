@@ -0,0 +1,212 @@
+use crate::GameState;
+use crate::components::broadphase::SpatialGrid;
+use crate::components::collider::*;
+use crate::components::playfield;
+use bevy::prelude::*;
+use rand::Rng;
+use std::time::Instant;
+
+/// Stress scene for the collision broadphase, started with `--benchmark`.
+/// Space switches between the grid and testing every pair.
+pub struct BenchmarkPlugin;
+
+impl Plugin for BenchmarkPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Benchmark), setup_benchmark)
+            .add_systems(
+                Update,
+                (
+                    move_benchmark_bullets,
+                    run_broadphase,
+                    update_benchmark_text,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Benchmark)),
+            )
+            .add_systems(
+                Update,
+                toggle_broadphase.run_if(in_state(GameState::Benchmark)),
+            );
+    }
+}
+
+const BENCHMARK_BULLETS: usize = 2000;
+const REPORT_INTERVAL: f32 = 1.0;
+
+#[derive(Component)]
+struct BenchmarkBullet {
+    velocity: Vec2,
+}
+
+#[derive(Component)]
+struct BenchmarkText;
+
+#[derive(Resource)]
+struct BenchmarkStats {
+    use_grid: bool,
+    elapsed: f32,
+    frames: u32,
+    broadphase_secs: f32,
+    tests: usize,
+    hits: usize,
+}
+
+impl BenchmarkStats {
+    fn reset(&mut self) {
+        self.elapsed = 0.0;
+        self.frames = 0;
+        self.broadphase_secs = 0.0;
+    }
+}
+
+fn setup_benchmark(mut commands: Commands) {
+    commands.insert_resource(BenchmarkStats {
+        use_grid: true,
+        elapsed: 0.0,
+        frames: 0,
+        broadphase_secs: 0.0,
+        tests: 0,
+        hits: 0,
+    });
+
+    let mut rng = rand::rng();
+    for i in 0..BENCHMARK_BULLETS {
+        let position = Vec2::new(
+            rng.random_range(-playfield::HALF_SIZE.x..playfield::HALF_SIZE.x),
+            rng.random_range(-playfield::HALF_SIZE.y..playfield::HALF_SIZE.y),
+        );
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        let speed = rng.random_range(50.0..200.0);
+        let (color, layer, mask) = if i % 2 == 0 {
+            (
+                Color::srgb(1.0, 0.8, 0.3),
+                CollisionLayers::ENEMY_BULLET,
+                CollisionLayers::PLAYER,
+            )
+        } else {
+            (
+                Color::srgb(0.4, 0.8, 1.0),
+                CollisionLayers::PLAYER_BULLET,
+                CollisionLayers::ENEMY,
+            )
+        };
+
+        commands.spawn((
+            Sprite::from_color(color, Vec2::splat(6.0)),
+            Transform::from_translation(position.extend(0.0)),
+            Collider {
+                shape: ColliderShape::Circle { radius: 3.0 },
+                layer,
+                mask,
+            },
+            BenchmarkBullet {
+                velocity: Vec2::from_angle(angle) * speed,
+            },
+        ));
+    }
+
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        },
+        BenchmarkText,
+    ));
+}
+
+fn move_benchmark_bullets(
+    mut query: Query<(&mut Transform, &mut BenchmarkBullet)>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut bullet) in &mut query {
+        transform.translation += (bullet.velocity * time.delta_secs()).extend(0.0);
+
+        let position = transform.translation.xy();
+        if position.x.abs() > playfield::HALF_SIZE.x {
+            bullet.velocity.x = -bullet.velocity.x.abs() * position.x.signum();
+        }
+        if position.y.abs() > playfield::HALF_SIZE.y {
+            bullet.velocity.y = -bullet.velocity.y.abs() * position.y.signum();
+        }
+    }
+}
+
+fn run_broadphase(
+    query: Query<(&Transform, &Collider)>,
+    mut grid: ResMut<SpatialGrid>,
+    mut stats: ResMut<BenchmarkStats>,
+) {
+    let shapes: Vec<WorldShape> = query
+        .iter()
+        .map(|(transform, collider)| collider.world_shape(transform))
+        .collect();
+
+    let start = Instant::now();
+    let (tests, hits) = if stats.use_grid {
+        grid.rebuild(shapes.iter().copied());
+        let pairs = grid.pairs();
+        let hits = pairs
+            .iter()
+            .filter(|(i, j)| shapes[*i].overlaps(&shapes[*j]))
+            .count();
+        (pairs.len(), hits)
+    } else {
+        let mut hits = 0;
+        for i in 0..shapes.len() {
+            for j in i + 1..shapes.len() {
+                if shapes[i].overlaps(&shapes[j]) {
+                    hits += 1;
+                }
+            }
+        }
+        (shapes.len() * shapes.len().saturating_sub(1) / 2, hits)
+    };
+
+    stats.broadphase_secs += start.elapsed().as_secs_f32();
+    stats.tests = tests;
+    stats.hits = hits;
+}
+
+fn update_benchmark_text(
+    mut stats: ResMut<BenchmarkStats>,
+    mut text_query: Query<&mut Text, With<BenchmarkText>>,
+    time: Res<Time>,
+) {
+    stats.elapsed += time.delta_secs();
+    stats.frames += 1;
+    if stats.elapsed < REPORT_INTERVAL {
+        return;
+    }
+
+    let frames = stats.frames as f32;
+    let report = format!(
+        "{} colliders - {} (Space to switch)\nframe: {:.2} ms ({:.0} fps)\ncollision: {:.2} ms\npair tests: {}  hits: {}",
+        BENCHMARK_BULLETS,
+        if stats.use_grid { "grid" } else { "all pairs" },
+        stats.elapsed / frames * 1000.0,
+        frames / stats.elapsed,
+        stats.broadphase_secs / frames * 1000.0,
+        stats.tests,
+        stats.hits,
+    );
+    info!("{}", report.replace('\n', ", "));
+    for mut text in &mut text_query {
+        text.0 = report.clone();
+    }
+
+    stats.reset();
+}
+
+fn toggle_broadphase(mut stats: ResMut<BenchmarkStats>, keyboard: Res<ButtonInput<KeyCode>>) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        stats.use_grid = !stats.use_grid;
+        stats.reset();
+    }
+}
@@ -1,5 +1,7 @@
 use crate::GameState;
 use crate::components::assets::*;
+use crate::components::input::Action;
+use crate::systems::input::Actions;
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 
@@ -26,12 +28,9 @@ fn setup_game_over(mut commands: Commands) {
     commands.insert_resource(ClearColor(Color::BLACK));
 }
 
-fn game_over_system(
-    mut next_state: ResMut<NextState<GameState>>,
-    keyboard: Res<ButtonInput<KeyCode>>,
-) {
+fn game_over_system(mut next_state: ResMut<NextState<GameState>>, actions: Actions) {
     //println!("Game Over: Press R to Restart");
-    if keyboard.just_pressed(KeyCode::KeyR) {
+    if actions.just_pressed(Action::Restart) {
         next_state.set(GameState::Playing);
     }
 }
@@ -1,9 +1,13 @@
+pub mod benchmark;
 pub mod clear;
+pub mod pause;
 pub mod playing;
 pub mod stage;
 pub mod title;
 
+pub use benchmark::BenchmarkPlugin;
 pub use clear::ClearPlugin;
+pub use pause::PausePlugin;
 pub use playing::PlayingPlugin;
 pub use stage::StageSelectPlugin;
 pub use title::TitlePlugin;
@@ -0,0 +1,164 @@
+use crate::components::input::{Action, ControlsPanel};
+use crate::components::player::Player;
+use crate::systems::input::{Actions, open_controls_panel};
+use crate::{GameState, PlayState};
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            toggle_pause.run_if(
+                in_state(PlayState::Running)
+                    .or(in_state(PlayState::Paused))
+                    .and(any_with_component::<Player>),
+            ),
+        )
+        .add_systems(OnEnter(PlayState::Paused), setup_pause_menu)
+        .add_systems(
+            Update,
+            pause_menu_system
+                .after(toggle_pause)
+                .run_if(in_state(PlayState::Paused)),
+        )
+        .add_systems(OnExit(PlayState::Paused), cleanup_pause_menu)
+        .add_systems(OnEnter(GameState::Restart), restart_stage);
+    }
+}
+
+#[derive(Component)]
+struct PauseMenu {
+    selected: usize,
+}
+
+#[derive(Component)]
+struct PauseMenuText;
+
+const PAUSE_ITEMS: [&str; 4] = ["Resume", "Restart Stage", "Settings", "Quit to Title"];
+
+fn toggle_pause(
+    actions: Actions,
+    state: Res<State<PlayState>>,
+    mut next_state: ResMut<NextState<PlayState>>,
+    panel_query: Query<(), With<ControlsPanel>>,
+) {
+    if !panel_query.is_empty() || !actions.just_pressed(Action::Pause) {
+        return;
+    }
+
+    match state.get() {
+        PlayState::Running => next_state.set(PlayState::Paused),
+        PlayState::Paused => next_state.set(PlayState::Running),
+    }
+}
+
+fn setup_pause_menu(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+) {
+    time.pause();
+    audio.pause();
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            GlobalZIndex(5),
+            PauseMenu { selected: 0 },
+        ))
+        .with_child((
+            Text::new(pause_text(0)),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextLayout::new_with_justify(JustifyText::Center),
+            PauseMenuText,
+        ));
+}
+
+fn pause_text(selected: usize) -> String {
+    let mut text = String::from("Paused\n\n");
+    for (i, item) in PAUSE_ITEMS.iter().enumerate() {
+        if i == selected {
+            text.push_str("\n> ");
+            text.push_str(item);
+            text.push_str(" <");
+        } else {
+            text.push_str("\n  ");
+            text.push_str(item);
+            text.push_str("  ");
+        }
+    }
+    text
+}
+
+fn pause_menu_system(
+    mut commands: Commands,
+    actions: Actions,
+    mut menu_query: Query<&mut PauseMenu>,
+    mut text_query: Query<&mut Text, With<PauseMenuText>>,
+    panel_query: Query<(), With<ControlsPanel>>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if !panel_query.is_empty() {
+        return;
+    }
+    let Ok(mut menu) = menu_query.single_mut() else {
+        return;
+    };
+
+    if actions.just_pressed(Action::MoveUp) {
+        menu.selected = (menu.selected + PAUSE_ITEMS.len() - 1) % PAUSE_ITEMS.len();
+    }
+    if actions.just_pressed(Action::MoveDown) {
+        menu.selected = (menu.selected + 1) % PAUSE_ITEMS.len();
+    }
+    for mut text in &mut text_query {
+        text.0 = pause_text(menu.selected);
+    }
+
+    if actions.just_pressed(Action::Back) {
+        next_play_state.set(PlayState::Running);
+    } else if actions.just_pressed(Action::Confirm) {
+        match menu.selected {
+            0 => next_play_state.set(PlayState::Running),
+            1 => next_game_state.set(GameState::Restart),
+            2 => open_controls_panel(&mut commands),
+            _ => next_game_state.set(GameState::Title),
+        }
+    }
+}
+
+fn cleanup_pause_menu(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+    menu_query: Query<Entity, With<PauseMenu>>,
+    panel_query: Query<Entity, With<ControlsPanel>>,
+) {
+    time.unpause();
+    audio.resume();
+
+    for entity in menu_query.iter().chain(panel_query.iter()) {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Leaving `Playing` and coming straight back runs every exit and enter system, which
+/// rebuilds the stage from the first wave.
+fn restart_stage(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Playing);
+}
@@ -1,15 +1,23 @@
 use crate::GameState;
+use crate::PlayState;
 use crate::components::assets::*;
 use crate::components::explosion::ExplosionTag;
+use crate::components::input::Action;
 use crate::components::player::*;
 use crate::components::stage::*;
 use crate::components::wave::*;
 use crate::systems::animation::AnimationPlugin;
 use crate::systems::assets::*;
+use crate::systems::bomb::BombPlugin;
+use crate::systems::boss::BossPlugin;
 use crate::systems::bullet::BulletPlugin;
+use crate::systems::charge::ChargePlugin;
 use crate::systems::collision::CollisionPlugin;
 use crate::systems::enemy::EnemyPlugin;
+use crate::systems::input::Actions;
 use crate::systems::item::ItemPlugin;
+use crate::systems::laser::LaserPlugin;
+use crate::systems::parry::ParryPlugin;
 use crate::systems::player::PlayerPlugin;
 use crate::systems::score::ScorePlugin;
 use crate::systems::sets::MySystemSet;
@@ -29,12 +37,17 @@ impl Plugin for PlayingPlugin {
             .add_plugins(AssetsPlugin)
             .add_plugins(PlayerPlugin)
             .add_plugins(EnemyPlugin)
+            .add_plugins(BossPlugin)
+            .add_plugins(BombPlugin)
             .add_plugins(BulletPlugin)
+            .add_plugins(LaserPlugin)
+            .add_plugins(ChargePlugin)
+            .add_plugins(ParryPlugin)
             .add_plugins(AnimationPlugin)
             .add_plugins(CollisionPlugin)
             .add_plugins(ScorePlugin)
             .add_plugins(ItemPlugin)
-            .add_systems(Update, playing_system.run_if(in_state(GameState::Playing)))
+            .add_systems(Update, playing_system.run_if(in_state(PlayState::Running)))
             .add_systems(OnExit(GameState::Playing), despawn_gameover_text)
             .add_systems(OnExit(GameState::Playing), stop_bgm)
             .add_systems(OnExit(GameState::Playing), despawn_background);
@@ -72,7 +85,7 @@ fn playing_system(
     query_explosion: Query<&ExplosionTag>,
     mut timer: ResMut<DeadTimer>,
     time: Res<Time>,
-    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Actions,
 ) {
     let player_explosion_exists = query_explosion
         .iter()
@@ -110,7 +123,7 @@ fn playing_system(
             ));
 
             timer.timer.pause();
-            if keyboard.just_pressed(KeyCode::Space) {
+            if actions.just_pressed(Action::Confirm) {
                 next_state.set(GameState::Title);
             }
         }
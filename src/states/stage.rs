@@ -1,144 +1,406 @@
 use crate::GameState;
+use crate::PlayState;
 use crate::components::assets::*;
+use crate::components::boss::BossEncounter;
 use crate::components::enemy::*;
+use crate::components::input::Action;
 use crate::components::stage::*;
 use crate::components::wave::*;
+use crate::systems::boss::spawn_boss;
+use crate::systems::collision::apply_collision_damage;
+use crate::systems::enemy::spawn_timeline_enemies;
+use crate::systems::input::Actions;
 use crate::systems::sets::MySystemSet;
+use bevy::asset::{LoadedFolder, RecursiveDependencyLoadState};
 use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
 use std::collections::HashMap;
 
 pub struct StageSelectPlugin;
 
 impl Plugin for StageSelectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(GameState::StageSelect),
-            load_stages.in_set(MySystemSet::LoadAssets),
-        )
-        .add_systems(
-            OnEnter(GameState::StageSelect),
-            setup_stage_select.after(MySystemSet::LoadAssets),
-        )
-        .add_systems(
-            Update,
-            update_stage_select.run_if(in_state(GameState::StageSelect)),
-        )
-        .add_systems(
-            Update,
-            stage_select_input.run_if(in_state(GameState::StageSelect)),
-        )
-        .add_systems(Update, update_waves.run_if(in_state(GameState::Playing)))
-        .add_systems(OnExit(GameState::StageSelect), cleanup_stage_select);
+        app.add_plugins(RonAssetPlugin::<StageAsset>::new(&["stage.ron"]))
+            .add_systems(
+                OnEnter(GameState::StageSelect),
+                reset_stages.in_set(MySystemSet::LoadAssets),
+            )
+            .add_systems(
+                OnEnter(GameState::StageSelect),
+                setup_stage_select.after(MySystemSet::LoadAssets),
+            )
+            .add_systems(
+                Update,
+                load_stages.run_if(
+                    in_state(GameState::StageSelect)
+                        .and(resource_exists::<EnemyDatabase>)
+                        .and(not(resource_exists::<StageDatabase>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                update_stage_select
+                    .run_if(in_state(GameState::StageSelect).and(resource_exists::<StageDatabase>)),
+            )
+            .add_systems(
+                Update,
+                stage_select_input
+                    .run_if(in_state(GameState::StageSelect).and(resource_exists::<StageDatabase>)),
+            )
+            .add_systems(
+                Update,
+                reload_stages
+                    .run_if(resource_exists::<EnemyDatabase>.and(resource_exists::<StageDatabase>)),
+            )
+            .add_systems(
+                Update,
+                count_defeated_enemies
+                    .after(apply_collision_damage)
+                    .before(update_waves)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                update_waves
+                    .after(spawn_timeline_enemies)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(OnEnter(GameState::Playing), reset_stage_progress)
+            .add_systems(OnExit(GameState::StageSelect), cleanup_stage_select);
     }
 }
 
-fn load_stages(mut commands: Commands, assets: Res<GameAssets>) {
-    let stage_db: StageDatabase = StageDatabase {
-        settings: vec![
-            StageSetting {
-                name: String::from("Stage1"),
-                background_image: assets.ground_texture.clone(),
-                waves: vec![
-                    Wave {
-                        number: 0,
-                        defeated_count: 0,
-                        target_count: 10,
-                        enemy_speed: 200.0,
-                        spawn_interval: 2.0,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoStraight, 1.0)]),
-                    },
-                    Wave {
-                        number: 1,
-                        defeated_count: 0,
-                        target_count: 20,
-                        enemy_speed: 300.0,
-                        spawn_interval: 0.3,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoZigzag, 1.0)]),
-                    },
-                    Wave {
-                        number: 2,
-                        defeated_count: 0,
-                        target_count: 10,
-                        enemy_speed: 500.0,
-                        spawn_interval: 0.1,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoHoming, 1.0)]),
-                    },
-                ],
-                current_index: 0,
-            },
-            StageSetting {
-                name: String::from("Stage2"),
-                background_image: assets.ocean_texture.clone(),
-                waves: vec![
-                    Wave {
-                        number: 0,
-                        defeated_count: 0,
-                        target_count: 10,
-                        enemy_speed: 100.0,
-                        spawn_interval: 0.1,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoStraight, 1.0)]),
-                    },
-                    Wave {
-                        number: 1,
-                        defeated_count: 0,
-                        target_count: 20,
-                        enemy_speed: 200.0,
-                        spawn_interval: 0.1,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoStraight, 1.0)]),
-                    },
-                    Wave {
-                        number: 2,
-                        defeated_count: 0,
-                        target_count: 10,
-                        enemy_speed: 500.0,
-                        spawn_interval: 0.1,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoStraight, 1.0)]),
-                    },
-                ],
-                current_index: 0,
-            },
-            StageSetting {
-                name: String::from("Stage3"),
-                background_image: assets.universe_texture.clone(),
-                waves: vec![
-                    Wave {
-                        number: 0,
-                        defeated_count: 0,
-                        target_count: 10,
-                        enemy_speed: 500.0,
-                        spawn_interval: 2.0,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoStraight, 1.0)]),
-                    },
-                    Wave {
-                        number: 1,
-                        defeated_count: 0,
-                        target_count: 20,
-                        enemy_speed: 500.0,
-                        spawn_interval: 1.0,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoStraight, 1.0)]),
-                    },
-                    Wave {
-                        number: 2,
-                        defeated_count: 0,
-                        target_count: 10,
-                        enemy_speed: 500.0,
-                        spawn_interval: 0.1,
-                        enemy_distribution: HashMap::from([(EnemyKind::DinoStraight, 1.0)]),
-                    },
-                ],
-                current_index: 0,
-            },
-        ],
-        current_index: 0,
+fn reset_stages(mut commands: Commands) {
+    commands.remove_resource::<StageDatabase>();
+}
+
+fn load_stages(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    asset_server: Res<AssetServer>,
+    folders: Res<Assets<LoadedFolder>>,
+    stage_assets: Res<Assets<StageAsset>>,
+    enemy_db: Res<EnemyDatabase>,
+) {
+    // A stage file that fails to parse marks the folder as failed; bevy already logs the
+    // parse error, so build the database from whatever did load.
+    match asset_server.recursive_dependency_load_state(&assets.stage_folder) {
+        RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_) => {}
+        _ => return,
+    }
+
+    let Some(folder) = folders.get(&assets.stage_folder) else {
+        return;
     };
-    commands.insert_resource(stage_db);
+
+    let mut handles: Vec<Handle<StageAsset>> = folder
+        .handles
+        .iter()
+        .filter_map(|handle| handle.clone().try_typed::<StageAsset>().ok())
+        .collect();
+    handles.sort_by_key(|handle| handle.path().map(|path| path.to_string()));
+
+    let mut settings: Vec<StageSetting> = Vec::new();
+    for handle in handles.iter() {
+        let Some(stage) = stage_assets.get(handle) else {
+            continue;
+        };
+
+        match build_stage_setting(stage, handle.id(), &asset_server, &enemy_db) {
+            Ok(setting) => settings.push(setting),
+            Err(e) => error!("skipping {:?}: {}", handle.path(), e),
+        }
+    }
+
+    if settings.is_empty() {
+        error!("no valid stage found in assets/stages");
+    }
+
+    commands.insert_resource(StageDatabase {
+        settings,
+        current_index: 0,
+    });
 }
 
-fn setup_stage_select(mut commands: Commands, stage_db: Res<StageDatabase>) {
-    let disp_text: String = create_disp_text(&stage_db);
+fn build_stage_setting(
+    stage: &StageAsset,
+    source: AssetId<StageAsset>,
+    asset_server: &AssetServer,
+    enemy_db: &EnemyDatabase,
+) -> Result<StageSetting, StageLoadError> {
+    if stage.waves.is_empty() {
+        return Err(StageLoadError::NoWaves {
+            stage: stage.name.clone(),
+        });
+    }
+
+    let mut waves: Vec<Wave> = Vec::new();
+    for (i, wave) in stage.waves.iter().enumerate() {
+        let mut enemy_distribution: HashMap<EnemyKind, f32> = HashMap::new();
+        for (name, rate) in wave.enemy_distribution.iter() {
+            let kind = EnemyKind(name.clone());
+            if !enemy_db.defs.contains_key(&kind) {
+                return Err(StageLoadError::UnknownEnemyKind {
+                    stage: stage.name.clone(),
+                    wave: i,
+                    kind: name.clone(),
+                });
+            }
+            if *rate > 0.0 {
+                enemy_distribution.insert(kind, *rate);
+            }
+        }
+
+        if wave.mode == WaveMode::Random && enemy_distribution.is_empty() {
+            return Err(StageLoadError::EmptyDistribution {
+                stage: stage.name.clone(),
+                wave: i,
+            });
+        }
+
+        // An empty formation would award its bonus on the frame it spawns.
+        let empty_formation = wave
+            .formations
+            .iter()
+            .chain(
+                wave.timeline
+                    .iter()
+                    .filter_map(|event| event.formation.as_ref()),
+            )
+            .any(|formation| formation.shape.offsets().is_empty());
+        if empty_formation {
+            return Err(StageLoadError::EmptyFormation {
+                stage: stage.name.clone(),
+                wave: i,
+            });
+        }
+
+        let mut timeline: Vec<SpawnEvent> = Vec::new();
+        for event in wave.timeline.iter() {
+            let kind = EnemyKind(event.enemy.clone());
+            if !enemy_db.defs.contains_key(&kind) {
+                return Err(StageLoadError::UnknownEnemyKind {
+                    stage: stage.name.clone(),
+                    wave: i,
+                    kind: event.enemy.clone(),
+                });
+            }
+
+            timeline.push(SpawnEvent {
+                time: event.time,
+                enemy: kind,
+                position: Vec2::from(event.position),
+                movement: event.movement.clone(),
+                formation: event.formation.clone(),
+            });
+        }
+        timeline.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        if wave.mode == WaveMode::Timeline && timeline.is_empty() {
+            return Err(StageLoadError::EmptyTimeline {
+                stage: stage.name.clone(),
+                wave: i,
+            });
+        }
+
+        waves.push(Wave {
+            number: i as u32,
+            defeated_count: 0,
+            target_count: wave.target_count,
+            enemy_speed: wave.enemy_speed,
+            spawn_interval: wave.spawn_interval,
+            enemy_distribution,
+            formation_rate: wave.formation_rate,
+            formations: wave.formations.clone(),
+            mode: wave.mode,
+            timeline,
+            elapsed: 0.0,
+            next_event: 0,
+        });
+    }
+
+    let mut boss = stage.boss.clone();
+    if let Some(boss) = boss.as_mut() {
+        if !enemy_db.defs.contains_key(&EnemyKind(boss.enemy.clone())) {
+            return Err(StageLoadError::UnknownBossEnemy {
+                stage: stage.name.clone(),
+                kind: boss.enemy.clone(),
+            });
+        }
+        if boss.phases.is_empty() {
+            return Err(StageLoadError::BossWithoutPhases {
+                stage: stage.name.clone(),
+            });
+        }
+        for (i, phase) in boss.phases.iter().enumerate() {
+            if phase.fire_interval <= 0.0 {
+                return Err(StageLoadError::InvalidBossFireInterval {
+                    stage: stage.name.clone(),
+                    phase: i,
+                    value: phase.fire_interval,
+                });
+            }
+            if let Some(value) = phase.bullet_pattern.invalid_burst_delay() {
+                return Err(StageLoadError::InvalidBossBurstDelay {
+                    stage: stage.name.clone(),
+                    phase: i,
+                    value,
+                });
+            }
+        }
+        boss.phases
+            .sort_by(|a, b| b.hp_threshold.total_cmp(&a.hp_threshold));
+    }
+
+    Ok(StageSetting {
+        name: stage.name.clone(),
+        background_image: asset_server.load(stage.background.clone()),
+        waves,
+        boss,
+        current_index: 0,
+        source,
+    })
+}
+
+fn reload_stages(
+    mut events: EventReader<AssetEvent<StageAsset>>,
+    asset_server: Res<AssetServer>,
+    stage_assets: Res<Assets<StageAsset>>,
+    enemy_db: Res<EnemyDatabase>,
+    mut stage_db: ResMut<StageDatabase>,
+    mut enemy_timer: Option<ResMut<EnemySpawnTimer>>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        let Some(stage) = stage_assets.get(*id) else {
+            continue;
+        };
+        let Some(stage_index) = stage_db.settings.iter().position(|s| s.source == *id) else {
+            info!(
+                "stage \"{}\" is new and will be listed on the next stage select",
+                stage.name
+            );
+            continue;
+        };
+
+        let mut setting = match build_stage_setting(stage, *id, &asset_server, &enemy_db) {
+            Ok(setting) => setting,
+            Err(e) => {
+                error!("keeping previous version of stage: {}", e);
+                continue;
+            }
+        };
+
+        // Keep the progress of a stage that is being played.
+        let old = &stage_db.settings[stage_index];
+        setting.current_index = old.current_index.min(setting.waves.len() - 1);
+        for (wave, old_wave) in setting.waves.iter_mut().zip(old.waves.iter()) {
+            wave.defeated_count = old_wave.defeated_count;
+            wave.elapsed = old_wave.elapsed;
+            wave.next_event = wave
+                .timeline
+                .iter()
+                .take_while(|event| event.time < old_wave.elapsed)
+                .count();
+        }
 
+        let changes = describe_stage_changes(old, &setting);
+        if changes.is_empty() {
+            info!("reloaded stage \"{}\": no wave changes", setting.name);
+        } else {
+            info!(
+                "reloaded stage \"{}\": {}",
+                setting.name,
+                changes.join(", ")
+            );
+        }
+
+        if stage_index == stage_db.current_index {
+            let interval = setting.waves[setting.current_index].spawn_interval;
+            if let Some(timer) = enemy_timer
+                .as_mut()
+                .filter(|timer| timer.timer.duration().as_secs_f32() != interval)
+            {
+                timer.timer = Timer::from_seconds(interval, TimerMode::Repeating);
+            }
+        }
+
+        stage_db.settings[stage_index] = setting;
+    }
+}
+
+fn describe_stage_changes(old: &StageSetting, new: &StageSetting) -> Vec<String> {
+    let mut changes: Vec<String> = Vec::new();
+
+    if old.name != new.name {
+        changes.push(format!("name {} -> {}", old.name, new.name));
+    }
+    if old.background_image != new.background_image {
+        changes.push(String::from("background changed"));
+    }
+    if old.waves.len() != new.waves.len() {
+        changes.push(format!(
+            "wave count {} -> {}",
+            old.waves.len(),
+            new.waves.len()
+        ));
+    }
+
+    for (i, (old_wave, new_wave)) in old.waves.iter().zip(new.waves.iter()).enumerate() {
+        if old_wave.target_count != new_wave.target_count {
+            changes.push(format!(
+                "wave {} target_count {} -> {}",
+                i, old_wave.target_count, new_wave.target_count
+            ));
+        }
+        if old_wave.enemy_speed != new_wave.enemy_speed {
+            changes.push(format!(
+                "wave {} enemy_speed {} -> {}",
+                i, old_wave.enemy_speed, new_wave.enemy_speed
+            ));
+        }
+        if old_wave.spawn_interval != new_wave.spawn_interval {
+            changes.push(format!(
+                "wave {} spawn_interval {} -> {}",
+                i, old_wave.spawn_interval, new_wave.spawn_interval
+            ));
+        }
+        if old_wave.enemy_distribution != new_wave.enemy_distribution {
+            changes.push(format!("wave {} enemy_distribution changed", i));
+        }
+        if old_wave.formation_rate != new_wave.formation_rate
+            || old_wave.formations != new_wave.formations
+        {
+            changes.push(format!("wave {} formations changed", i));
+        }
+        if old_wave.mode != new_wave.mode {
+            changes.push(format!(
+                "wave {} mode {:?} -> {:?}",
+                i, old_wave.mode, new_wave.mode
+            ));
+        }
+        if old_wave.timeline != new_wave.timeline {
+            changes.push(format!(
+                "wave {} timeline {} -> {} events",
+                i,
+                old_wave.timeline.len(),
+                new_wave.timeline.len()
+            ));
+        }
+    }
+
+    if old.boss != new.boss {
+        changes.push(String::from("boss changed"));
+    }
+
+    changes
+}
+
+fn setup_stage_select(mut commands: Commands) {
     commands.spawn((
-        Text::new(disp_text),
+        Text::new("Loading..."),
         StageSelectScreenTag,
         TextFont {
             font_size: 20.0,
@@ -169,6 +431,10 @@ fn update_stage_select(
 fn create_disp_text(stage_db: &Res<StageDatabase>) -> String {
     let mut disp_text: String = String::new();
 
+    if stage_db.settings.is_empty() {
+        return String::from("No stages found");
+    }
+
     for i in 0..stage_db.settings.len() {
         if i == stage_db.current_index {
             disp_text = format!("{} > {}\n\n", disp_text, stage_db.settings[i].name);
@@ -182,39 +448,86 @@ fn create_disp_text(stage_db: &Res<StageDatabase>) -> String {
 
 fn stage_select_input(
     mut next_state: ResMut<NextState<GameState>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    actions: Actions,
     mut stage_db: ResMut<StageDatabase>,
 ) {
+    if stage_db.settings.is_empty() {
+        return;
+    }
+
     let stage_num: isize = stage_db.settings.len() as isize;
     let index: isize = stage_db.current_index as isize;
-    if keyboard_input.just_pressed(KeyCode::ArrowUp) || keyboard_input.just_pressed(KeyCode::KeyK) {
+    if actions.just_pressed(Action::MoveUp) {
         stage_db.current_index = ((index - 1 + stage_num) % stage_num) as usize;
     }
 
-    if keyboard_input.just_pressed(KeyCode::ArrowDown) || keyboard_input.just_pressed(KeyCode::KeyJ)
-    {
+    if actions.just_pressed(Action::MoveDown) {
         stage_db.current_index = ((index + 1 + stage_num) % stage_num) as usize;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Space) {
+    if actions.just_pressed(Action::Confirm) {
         next_state.set(GameState::Playing);
+    } else if actions.just_pressed(Action::Back) {
+        next_state.set(GameState::Title);
+    }
+}
+
+fn reset_stage_progress(mut stage_db: ResMut<StageDatabase>) {
+    let stage_index = stage_db.current_index;
+    stage_db.settings[stage_index].reset_progress();
+}
+
+fn count_defeated_enemies(
+    mut events: EventReader<EnemyDefeated>,
+    mut stage_db: ResMut<StageDatabase>,
+) {
+    let stage_index = stage_db.current_index;
+    let wave_index = stage_db.settings[stage_index].current_index;
+    for _ in events.read() {
+        stage_db.settings[stage_index].waves[wave_index].defeated_count += 1;
     }
 }
 
 fn update_waves(
+    mut commands: Commands,
     mut stage_db: ResMut<StageDatabase>,
     mut enemy_timer: ResMut<EnemySpawnTimer>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut encounter: ResMut<BossEncounter>,
+    enemy_db: Res<EnemyDatabase>,
+    enemy_query: Query<(), With<Enemy>>,
 ) {
     let stage_index = stage_db.current_index;
     let mut wave_index = stage_db.settings[stage_index].current_index;
     let wave_length = stage_db.settings[stage_index].waves.len();
-    let wave = stage_db.settings[stage_index].waves[wave_index].clone();
+    let wave = &stage_db.settings[stage_index].waves[wave_index];
 
-    if wave.defeated_count >= wave.target_count {
+    let cleared = match wave.mode {
+        WaveMode::Random => wave.defeated_count >= wave.target_count,
+        WaveMode::Timeline => wave.next_event >= wave.timeline.len() && enemy_query.is_empty(),
+    };
+
+    if cleared {
         if wave_index == wave_length - 1 {
             enemy_timer.timer.pause();
-            next_state.set(GameState::Clear);
+
+            // A boss stops the regular spawns and the stage clears once it is down.
+            match (*encounter, &stage_db.settings[stage_index].boss) {
+                (BossEncounter::Pending, Some(boss)) => {
+                    // Checked at load, but a reload may have removed the definition since.
+                    let Some(enemy_def) = enemy_db.defs.get(&EnemyKind(boss.enemy.clone())) else {
+                        error!("unknown boss enemy \"{}\", skipping the boss", boss.enemy);
+                        next_state.set(GameState::Clear);
+                        return;
+                    };
+                    spawn_boss(&mut commands, boss, enemy_def);
+                    *encounter = BossEncounter::Fighting {
+                        score_bonus: boss.score_bonus,
+                    };
+                }
+                (BossEncounter::Fighting { .. }, _) => {}
+                _ => next_state.set(GameState::Clear),
+            }
             return;
         }
 
@@ -1,4 +1,6 @@
 use crate::GameState;
+use crate::components::input::{Action, ControlsPanel};
+use crate::systems::input::{Actions, open_controls_panel};
 use bevy::prelude::*;
 
 pub struct TitlePlugin;
@@ -11,10 +13,17 @@ impl Plugin for TitlePlugin {
     }
 }
 
+#[derive(Component)]
+struct TitleMenu {
+    selected: usize,
+}
+
+const TITLE_ITEMS: [&str; 2] = ["Start", "Controls"];
+
 fn setup_menu(mut commands: Commands) {
     commands.insert_resource(ClearColor(Color::BLACK));
     commands.spawn((
-        Text::new("Defeat the Dinosaur\n\n\n\n- Press Space to Start -"),
+        Text::new(title_text(0)),
         TextFont {
             font_size: 20.0,
             ..default()
@@ -32,12 +41,53 @@ fn setup_menu(mut commands: Commands) {
             //left: Val::Percent(20.0),
             ..default()
         },
+        TitleMenu { selected: 0 },
     ));
 }
 
-fn menu_system(mut next_state: ResMut<NextState<GameState>>, keyboard: Res<ButtonInput<KeyCode>>) {
-    if keyboard.just_pressed(KeyCode::Space) {
-        next_state.set(GameState::StageSelect);
+fn title_text(selected: usize) -> String {
+    let mut text = String::from("Defeat the Dinosaur\n\n\n");
+    for (i, item) in TITLE_ITEMS.iter().enumerate() {
+        if i == selected {
+            text.push_str("\n> ");
+            text.push_str(item);
+            text.push_str(" <");
+        } else {
+            text.push_str("\n  ");
+            text.push_str(item);
+            text.push_str("  ");
+        }
+    }
+    text
+}
+
+fn menu_system(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    actions: Actions,
+    mut menu_query: Query<(&mut Text, &mut TitleMenu)>,
+    panel_query: Query<(), With<ControlsPanel>>,
+) {
+    if !panel_query.is_empty() {
+        return;
+    }
+    let Ok((mut text, mut menu)) = menu_query.single_mut() else {
+        return;
+    };
+
+    if actions.just_pressed(Action::MoveUp) {
+        menu.selected = (menu.selected + TITLE_ITEMS.len() - 1) % TITLE_ITEMS.len();
+    }
+    if actions.just_pressed(Action::MoveDown) {
+        menu.selected = (menu.selected + 1) % TITLE_ITEMS.len();
+    }
+    text.0 = title_text(menu.selected);
+
+    if actions.just_pressed(Action::Confirm) {
+        match menu.selected {
+            0 => next_state.set(GameState::StageSelect),
+            _ => open_controls_panel(&mut commands),
+        }
     }
 }
 
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
 use crate::GameState;
+use crate::PlayState;
 use crate::components::animation::AnimationConfig;
 use crate::components::animation::AnimationType;
 
@@ -10,7 +11,7 @@ impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            execute_animation.run_if(in_state(GameState::Playing)),
+            execute_animation.run_if(in_state(PlayState::Running)),
         )
         .add_systems(OnExit(GameState::Playing), cleanup_animations);
     }
@@ -22,18 +22,17 @@ fn load_assets(
 ) {
     let game_assets: GameAssets = GameAssets {
         player_texture: asset_server.load("textures/rocket.png"),
-        dino_assets: load_dino(&asset_server, &mut texture_atlas_layouts),
         explosion_assets: load_explosion(&asset_server, &mut texture_atlas_layouts),
         fill_heart_texture: asset_server.load("textures/fill_heart.png"),
         empty_heart_texture: asset_server.load("textures/empty_heart.png"),
-        rapid_fire_texture: asset_server.load("textures/rapid_fire.png"),
+        power_up_texture: asset_server.load("textures/rapid_fire.png"),
         piercing_shot_texture: asset_server.load("textures/piercing_shot.png"),
         apple_texture: asset_server.load("textures/apple.png"),
-        blue_egg_texture: asset_server.load("textures/blue_egg.png"),
+        bomb_texture: asset_server.load("textures/bomb.png"),
+        weapon_texture: asset_server.load("textures/weapon.png"),
         yellow_egg_texture: asset_server.load("textures/yellow_egg.png"),
-        ground_texture: asset_server.load("textures/ground.png"),
-        ocean_texture: asset_server.load("textures/ocean.png"),
-        universe_texture: asset_server.load("textures/universe.png"),
+        stage_folder: asset_server.load_folder("stages"),
+        enemy_folder: asset_server.load_folder("enemies"),
 
         shoot_sound: asset_server.load("sounds/shoot.ogg"),
         damage_sound: asset_server.load("sounds/damage.ogg"),
@@ -62,19 +61,67 @@ fn load_explosion(
     };
 }
 
-fn load_dino(
-    asset_server: &Res<AssetServer>,
-    texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
-) -> AnimAsset {
-    let texture = asset_server.load("textures/Dino.png");
-    let layout = TextureAtlasLayout::from_grid(UVec2::new(30, 30), 2, 1, None, None);
-    let layout_handle = texture_atlas_layouts.add(layout);
+pub fn load_visual(
+    visual: &VisualAsset,
+    asset_server: &AssetServer,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    previous: Option<&AssetVisual>,
+) -> AssetVisual {
+    match visual {
+        VisualAsset::Static { texture } => AssetVisual::Static(asset_server.load(texture.clone())),
+        VisualAsset::Animated {
+            texture,
+            tile_size,
+            columns,
+            rows,
+            fps,
+        } => {
+            let layout = TextureAtlasLayout::from_grid(
+                UVec2::new(tile_size.0, tile_size.1),
+                *columns,
+                *rows,
+                None,
+                None,
+            );
+            let last = (columns * rows).max(1) as usize - 1;
 
-    let enemy_config = AnimationConfig::new(0, 1, 10, AnimationType::Loop);
+            // Overwrite the layout of the visual being reloaded instead of adding another.
+            let layout = match previous {
+                Some(AssetVisual::Animated(previous)) => {
+                    match texture_atlas_layouts.get_mut(&previous.layout) {
+                        Some(old) => {
+                            *old = layout;
+                            previous.layout.clone()
+                        }
+                        None => texture_atlas_layouts.add(layout),
+                    }
+                }
+                _ => texture_atlas_layouts.add(layout),
+            };
 
-    return AnimAsset {
-        texture,
-        layout: layout_handle.clone(),
-        anim_config: enemy_config,
-    };
+            AssetVisual::Animated(AnimAsset {
+                texture: asset_server.load(texture.clone()),
+                layout,
+                anim_config: AnimationConfig::new(0, last, (*fps).max(1), AnimationType::Loop),
+            })
+        }
+    }
+}
+
+/// Builds the sprite for a visual. Animated visuals also return the config to insert next
+/// to it.
+pub fn sprite_from_visual(visual: &AssetVisual) -> (Sprite, Option<AnimationConfig>) {
+    match visual {
+        AssetVisual::Static(texture) => (Sprite::from_image(texture.clone()), None),
+        AssetVisual::Animated(anim) => (
+            Sprite::from_atlas_image(
+                anim.texture.clone(),
+                TextureAtlas {
+                    layout: anim.layout.clone(),
+                    index: anim.anim_config.first_sprite_index,
+                },
+            ),
+            Some(anim.anim_config.clone()),
+        ),
+    }
 }
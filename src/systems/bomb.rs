@@ -0,0 +1,166 @@
+use crate::components::assets::*;
+use crate::components::bullet::Bullet;
+use crate::components::collider::*;
+use crate::components::enemy::{Enemy, EnemyDefeated, Health, HitFlash};
+use crate::components::formation::FormationMember;
+use crate::components::input::Action;
+use crate::components::player::*;
+use crate::components::playfield;
+use crate::systems::collision::apply_collision_damage;
+use crate::systems::input::Actions;
+use crate::systems::sets::MySystemSet;
+use crate::{GameState, PlayState};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+pub struct BombPlugin;
+
+impl Plugin for BombPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            spawn_bomb_text.after(MySystemSet::LoadAssets),
+        )
+        .add_systems(
+            Update,
+            use_bomb
+                .before(apply_collision_damage)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(
+            Update,
+            update_bomb_text.run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(Update, fade_bomb_flash.run_if(in_state(PlayState::Running)))
+        .add_systems(OnExit(GameState::Playing), cleanup_bombs);
+    }
+}
+
+const BOMB_DAMAGE: u32 = 20;
+const BOMB_INVINCIBLE_SECS: f32 = 3.0;
+const BOMB_FLASH_SECS: f32 = 0.4;
+const BOMB_FLASH_ALPHA: f32 = 0.8;
+
+fn spawn_bomb_text(mut commands: Commands) {
+    commands
+        .spawn((
+            Text::new("Bombs: "),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                right: Val::Px(8.0),
+                ..default()
+            },
+            BombText,
+        ))
+        .with_child((TextSpan::default(), BombText));
+}
+
+type BombTargetData = (
+    Entity,
+    &'static mut Health,
+    &'static Transform,
+    Option<&'static FormationMember>,
+);
+
+/// What a bomb clears: every bullet and enemy on the field.
+#[derive(SystemParam)]
+struct BombTargets<'w, 's> {
+    bullets: Query<'w, 's, (Entity, &'static Collider), With<Bullet>>,
+    enemies: Query<'w, 's, BombTargetData, With<Enemy>>,
+}
+
+fn use_bomb(
+    mut commands: Commands,
+    actions: Actions,
+    mut player_query: Query<&mut Player>,
+    mut targets: BombTargets,
+    mut enemy_defeated: EventWriter<EnemyDefeated>,
+    assets: Res<GameAssets>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+) {
+    if !actions.just_pressed(Action::Bomb) {
+        return;
+    }
+    let Ok(mut player) = player_query.single_mut() else {
+        return;
+    };
+    if player.bombs == 0 {
+        return;
+    }
+
+    player.bombs -= 1;
+    player.invincible_timer = Timer::from_seconds(BOMB_INVINCIBLE_SECS, TimerMode::Once);
+
+    for (entity, collider) in &targets.bullets {
+        if collider.layer == CollisionLayers::ENEMY_BULLET {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (entity, mut health, transform, member) in &mut targets.enemies {
+        let position = transform.translation;
+        if !playfield::contains(position.xy()) || health.current == 0 {
+            continue;
+        }
+
+        health.current = health.current.saturating_sub(BOMB_DAMAGE);
+        if health.current > 0 {
+            commands.entity(entity).try_insert(HitFlash::default());
+        } else {
+            commands.entity(entity).despawn();
+            enemy_defeated.write(EnemyDefeated {
+                position,
+                formation: member.map(|member| member.leader),
+            });
+        }
+    }
+
+    commands.spawn((
+        Sprite::from_color(
+            Color::srgba(1.0, 1.0, 1.0, BOMB_FLASH_ALPHA),
+            playfield::HALF_SIZE * 2.0,
+        ),
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        BombFlash {
+            timer: Timer::from_seconds(BOMB_FLASH_SECS, TimerMode::Once),
+        },
+    ));
+    audio.play(assets.explosion_sound.clone()).with_volume(0.3);
+}
+
+fn update_bomb_text(mut query: Query<&mut TextSpan, With<BombText>>, player_query: Query<&Player>) {
+    let Ok(player) = player_query.single() else {
+        return;
+    };
+
+    for mut span in &mut query {
+        **span = format!("{}", player.bombs);
+    }
+}
+
+fn fade_bomb_flash(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut BombFlash, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    for (entity, mut flash, mut sprite) in &mut query {
+        flash.timer.tick(time.delta());
+        if flash.timer.finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite
+                .color
+                .set_alpha(BOMB_FLASH_ALPHA * (1.0 - flash.timer.fraction()));
+        }
+    }
+}
+
+type BombEntities = Or<(With<BombText>, With<BombFlash>)>;
+
+fn cleanup_bombs(mut commands: Commands, query: Query<Entity, BombEntities>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,295 @@
+use crate::GameState;
+use crate::PlayState;
+use crate::components::boss::*;
+use crate::components::bullet::BulletEmitter;
+use crate::components::collider::*;
+use crate::components::enemy::*;
+use crate::components::player::Player;
+use crate::components::score::Score;
+use crate::systems::assets::sprite_from_visual;
+use bevy::prelude::*;
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), initialize_encounter)
+            .add_systems(
+                Update,
+                boss_phase_system.run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                boss_movement
+                    .after(boss_phase_system)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                boss_part_follow
+                    .after(boss_movement)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                update_boss_hp_bar.run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                check_boss_defeated.run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_boss);
+    }
+}
+
+const BOSS_ENTRY_Y: f32 = 420.0;
+const BOSS_HOME_Y: f32 = 200.0;
+const BOSS_ENTRY_SPEED: f32 = 120.0;
+/// How quickly a hovering boss drifts back home, per second. Close to the old 5% per
+/// frame at 60 fps.
+const BOSS_HOVER_RATE: f32 = 3.0;
+
+fn initialize_encounter(mut commands: Commands) {
+    commands.insert_resource(BossEncounter::Pending);
+}
+
+pub fn spawn_boss(commands: &mut Commands, boss: &BossSetting, enemy_def: &EnemyDefinition) {
+    let phase = &boss.phases[0];
+    let mut boss_def = enemy_def.clone();
+    boss_def.fire_interval = phase.fire_interval;
+    boss_def.bullet_pattern = phase.bullet_pattern.clone();
+
+    let (sprite, anim_config) = sprite_from_visual(&boss_def.visual);
+    let mut entity = commands.spawn((
+        sprite,
+        Transform::from_xyz(0.0, BOSS_ENTRY_Y, 0.0).with_scale(Vec3::splat(boss.scale)),
+        Collider {
+            shape: ColliderShape::Rectangle {
+                size: boss_def.collider_size,
+            },
+            layer: CollisionLayers::ENEMY,
+            mask: CollisionLayers::PLAYER | CollisionLayers::PLAYER_BULLET,
+        },
+        Enemy,
+        Health {
+            current: boss_def.max_hp,
+            max: boss_def.max_hp,
+        },
+        Boss {
+            phases: boss.phases.clone(),
+            phase_index: 0,
+            home: Vec2::new(0.0, BOSS_HOME_Y),
+            entered: false,
+            elapsed: 0.0,
+        },
+    ));
+    if let Some(anim_config) = anim_config {
+        entity.insert(anim_config);
+    }
+    if boss_def.bullet.is_some() {
+        entity.insert((
+            EnemyFireTimer {
+                timer: Timer::from_seconds(boss_def.fire_interval, TimerMode::Repeating),
+            },
+            BulletEmitter::default(),
+        ));
+    }
+    let boss_entity = entity.insert(boss_def).id();
+
+    for part in boss.parts.iter() {
+        let offset = Vec2::from(part.offset);
+        commands.spawn((
+            Transform::from_xyz(offset.x, BOSS_ENTRY_Y + offset.y, 0.0),
+            Collider {
+                shape: ColliderShape::Rectangle {
+                    size: Vec2::from(part.size),
+                },
+                layer: CollisionLayers::ENEMY,
+                mask: CollisionLayers::PLAYER | CollisionLayers::PLAYER_BULLET,
+            },
+            Enemy,
+            BossPart {
+                boss: boss_entity,
+                offset,
+            },
+        ));
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(48.0),
+                left: Val::Percent(10.0),
+                width: Val::Percent(80.0),
+                height: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.3, 0.0, 0.0)),
+            BossHpBar,
+        ))
+        .with_child((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.9, 0.1, 0.1)),
+            BossHpBarFill,
+        ));
+}
+
+type BossPhaseData = (
+    &'static Health,
+    &'static mut Boss,
+    &'static mut EnemyDefinition,
+    Option<&'static mut EnemyFireTimer>,
+    Option<&'static mut BulletEmitter>,
+);
+
+fn boss_phase_system(mut query: Query<BossPhaseData>) {
+    for (health, mut boss, mut enemy_def, fire_timer, emitter) in &mut query {
+        let ratio = health.current as f32 / health.max.max(1) as f32;
+        let phase_index = boss
+            .phases
+            .iter()
+            .rposition(|phase| ratio <= phase.hp_threshold)
+            .unwrap_or(0);
+
+        if phase_index == boss.phase_index {
+            continue;
+        }
+
+        let phase = boss.phases[phase_index].clone();
+        boss.phase_index = phase_index;
+        boss.elapsed = 0.0;
+        enemy_def.fire_interval = phase.fire_interval;
+        enemy_def.bullet_pattern = phase.bullet_pattern;
+
+        if let Some(mut fire_timer) = fire_timer {
+            fire_timer.timer = Timer::from_seconds(phase.fire_interval, TimerMode::Repeating);
+        }
+        if let Some(mut emitter) = emitter {
+            *emitter = BulletEmitter::default();
+        }
+    }
+}
+
+fn boss_movement(
+    mut boss_query: Query<(&mut Transform, &mut Boss)>,
+    player_query: Query<&Transform, (With<Player>, Without<Boss>)>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut boss) in &mut boss_query {
+        if !boss.entered {
+            transform.translation.y -= BOSS_ENTRY_SPEED * time.delta_secs();
+            if transform.translation.y <= boss.home.y {
+                transform.translation.y = boss.home.y;
+                boss.entered = true;
+            }
+            continue;
+        }
+
+        boss.elapsed += time.delta_secs();
+        let elapsed = boss.elapsed;
+        let home = boss.home;
+
+        match boss.phases[boss.phase_index].movement {
+            BossMovePattern::Hover => {
+                let t = 1.0 - (-BOSS_HOVER_RATE * time.delta_secs()).exp();
+                transform.translation.x = transform.translation.x.lerp(home.x, t);
+                transform.translation.y = transform.translation.y.lerp(home.y, t);
+            }
+            BossMovePattern::Sway {
+                amplitude,
+                frequency,
+            } => {
+                let angle = elapsed * frequency * std::f32::consts::TAU;
+                transform.translation.x = home.x + angle.sin() * amplitude;
+                transform.translation.y = home.y;
+            }
+            BossMovePattern::Circle { radius, frequency } => {
+                let angle = elapsed * frequency * std::f32::consts::TAU;
+                transform.translation.x = home.x + angle.sin() * radius;
+                transform.translation.y = home.y + angle.cos() * radius - radius;
+            }
+            BossMovePattern::Chase { speed } => {
+                if let Ok(player) = player_query.single() {
+                    let dx = player.translation.x - transform.translation.x;
+                    let step = speed * time.delta_secs();
+                    transform.translation.x += dx.clamp(-step, step);
+                }
+                transform.translation.y = home.y;
+            }
+        }
+    }
+}
+
+fn boss_part_follow(
+    mut commands: Commands,
+    mut part_query: Query<(Entity, &mut Transform, &BossPart), Without<Boss>>,
+    boss_query: Query<&Transform, With<Boss>>,
+) {
+    for (entity, mut transform, part) in &mut part_query {
+        match boss_query.get(part.boss) {
+            Ok(boss_transform) => {
+                transform.translation = boss_transform.translation + part.offset.extend(0.0);
+            }
+            Err(_) => {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn update_boss_hp_bar(
+    boss_query: Query<&Health, With<Boss>>,
+    mut fill_query: Query<&mut Node, With<BossHpBarFill>>,
+) {
+    let Ok(health) = boss_query.single() else {
+        return;
+    };
+
+    for mut node in &mut fill_query {
+        let ratio = health.current as f32 / health.max.max(1) as f32;
+        node.width = Val::Percent(ratio * 100.0);
+    }
+}
+
+fn check_boss_defeated(
+    mut commands: Commands,
+    mut encounter: ResMut<BossEncounter>,
+    mut score: ResMut<Score>,
+    mut removed: RemovedComponents<Boss>,
+    bar_query: Query<Entity, With<BossHpBar>>,
+) {
+    let BossEncounter::Fighting { score_bonus } = *encounter else {
+        return;
+    };
+
+    if removed.read().next().is_none() {
+        return;
+    }
+
+    score.score += score_bonus;
+    *encounter = BossEncounter::Defeated;
+
+    for entity in &bar_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+type BossOrPart = Or<(With<Boss>, With<BossPart>)>;
+
+fn cleanup_boss(
+    mut commands: Commands,
+    boss_query: Query<Entity, BossOrPart>,
+    bar_query: Query<Entity, With<BossHpBar>>,
+) {
+    for entity in &boss_query {
+        commands.entity(entity).despawn();
+    }
+    for entity in &bar_query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,73 +1,185 @@
 use crate::GameState;
+use crate::PlayState;
 use crate::components::assets::*;
 use crate::components::bullet::*;
 use crate::components::collider::*;
+use crate::components::enemy::{Enemy, Health};
+use crate::components::input::Action;
 use crate::components::player::Player;
+use crate::components::playfield;
+use crate::components::weapon::*;
+use crate::systems::input::Actions;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 use std::time::Duration;
 
 pub struct BulletPlugin;
 
+const HOMING_TURN_RATE: f32 = 4.0;
+const HOMING_LIFETIME: f32 = 3.0;
+/// How far past the side edges of the playfield bullets are still kept.
+const SIDE_MARGIN: f32 = 20.0;
+
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(BulletCooldown {
             timer: Timer::from_seconds(2.0, TimerMode::Repeating),
         })
-        .add_systems(Update, update_cooldown.run_if(in_state(GameState::Playing)))
-        .add_systems(Update, bullet_spawn.run_if(in_state(GameState::Playing)))
-        .add_systems(Update, bullet_movement.run_if(in_state(GameState::Playing)))
+        .add_systems(Update, update_cooldown.run_if(in_state(PlayState::Running)))
+        .add_systems(Update, bullet_spawn.run_if(in_state(PlayState::Running)))
+        .add_systems(
+            Update,
+            steer_homing_bullets
+                .before(bullet_movement)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(Update, bullet_movement.run_if(in_state(PlayState::Running)))
         .add_systems(OnExit(GameState::Playing), cleanup_bullets);
     }
 }
 
 fn update_cooldown(query: Query<&Player>, mut cooldown: ResMut<BulletCooldown>) {
     if let Ok(player) = query.single() {
-        if Duration::from_secs_f32(player.shoot_interval.clone()) != cooldown.timer.duration() {
-            cooldown.timer = Timer::from_seconds(player.shoot_interval, TimerMode::Repeating);
+        let interval = player.shoot_interval * player.weapon.interval_scale();
+        if Duration::from_secs_f32(interval) != cooldown.timer.duration() {
+            cooldown.timer = Timer::from_seconds(interval, TimerMode::Repeating);
         }
     }
 }
 
-fn bullet_spawn(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    query: Query<&Transform, With<Player>>,
+/// What decides whether the normal gun may fire this frame.
+#[derive(SystemParam)]
+pub struct Gun<'w> {
+    cooldown: ResMut<'w, BulletCooldown>,
+    charge: Res<'w, ChargeShot>,
+}
+
+pub fn bullet_spawn(
+    actions: Actions,
+    query: Query<(&Transform, &Player)>,
     mut commands: Commands,
-    mut cooldown: ResMut<BulletCooldown>,
+    mut gun: Gun,
     time: Res<Time>,
     audio: Res<bevy_kira_audio::prelude::Audio>,
     assets: Res<GameAssets>,
 ) {
-    cooldown.timer.tick(time.delta());
-
-    if keyboard.pressed(KeyCode::Space) && cooldown.timer.finished() {
-        if let Ok(player_transform) = query.single() {
-            let player_pos = player_transform.translation;
-            commands.spawn((
-                Sprite {
-                    color: Color::srgb(1.0, 1.0, 0.0),
-                    custom_size: Some(Vec2::new(3.0, 3.0)),
-                    ..default()
-                },
-                Transform {
-                    translation: Vec3::new(player_pos.x, player_pos.y + 30.0, 0.0),
-                    ..default()
-                },
-                Collider {
-                    shape: ColliderShape::Rectangle {
-                        size: Vec2::new(3.0, 3.0),
-                    },
-                    tag: ColliderTag::Bullet,
-                },
-                Bullet {
-                    is_player: true,
-                    speed: 600.0,
-                },
-            ));
-
-            audio.play(assets.shoot_sound.clone()).with_volume(0.2);
-            cooldown.timer.reset();
+    gun.cooldown.timer.tick(time.delta());
+
+    // The gun goes quiet while a charge shot builds.
+    if !actions.pressed(Action::Fire) || gun.charge.is_charging() || !gun.cooldown.timer.finished()
+    {
+        return;
+    }
+    let Ok((player_transform, player)) = query.single() else {
+        return;
+    };
+
+    let muzzle = player_transform.translation.xy() + Vec2::new(0.0, 30.0);
+    let color = player.weapon.kind.color();
+    let shots = player.weapon.volley(player.bullet_damage, player.focused);
+    if shots.is_empty() {
+        return;
+    }
+
+    for shot in shots {
+        spawn_player_bullet(
+            &mut commands,
+            muzzle + shot.offset,
+            &shot,
+            color,
+            player.piercing,
+        );
+    }
+
+    audio.play(assets.shoot_sound.clone()).with_volume(0.2);
+    gun.cooldown.timer.reset();
+}
+
+pub fn spawn_player_bullet(
+    commands: &mut Commands,
+    position: Vec2,
+    shot: &Shot,
+    color: Color,
+    piercing: bool,
+) {
+    // Sprites and colliders are drawn pointing up, so turn them to face their velocity.
+    let rotation = Quat::from_rotation_z(Vec2::Y.angle_to(shot.velocity));
+
+    let mut bullet = commands.spawn((
+        Sprite {
+            color,
+            custom_size: Some(shot.size),
+            ..default()
+        },
+        Transform::from_translation(position.extend(0.0)).with_rotation(rotation),
+        Collider {
+            shape: ColliderShape::Rectangle { size: shot.size },
+            layer: CollisionLayers::PLAYER_BULLET,
+            mask: CollisionLayers::ENEMY,
+        },
+        Bullet {
+            velocity: shot.velocity,
+            damage: shot.damage,
+            piercing: piercing || shot.piercing,
+            hits: Vec::new(),
+        },
+    ));
+    if shot.homing {
+        bullet.insert(Homing {
+            target: None,
+            turn_rate: HOMING_TURN_RATE,
+            lifetime: Timer::from_seconds(HOMING_LIFETIME, TimerMode::Once),
+        });
+    }
+}
+
+fn steer_homing_bullets(
+    mut commands: Commands,
+    mut bullet_query: Query<(Entity, &mut Transform, &mut Bullet, &mut Homing), Without<Enemy>>,
+    enemy_query: Query<(Entity, &Transform, &Health), With<Enemy>>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut bullet, mut homing) in &mut bullet_query {
+        homing.lifetime.tick(time.delta());
+        if homing.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
         }
+
+        let position = transform.translation.xy();
+        let alive = |target: Entity| {
+            enemy_query
+                .get(target)
+                .is_ok_and(|(_, _, health)| health.current > 0)
+        };
+        if !homing.target.is_some_and(alive) {
+            homing.target = enemy_query
+                .iter()
+                .filter(|(_, enemy, health)| {
+                    health.current > 0 && enemy.translation.y < playfield::HALF_SIZE.y
+                })
+                .min_by(|(_, a, _), (_, b, _)| {
+                    let a = a.translation.xy().distance_squared(position);
+                    let b = b.translation.xy().distance_squared(position);
+                    a.total_cmp(&b)
+                })
+                .map(|(target, ..)| target);
+        }
+
+        let Some(Ok((_, target, _))) = homing.target.map(|target| enemy_query.get(target)) else {
+            continue;
+        };
+        let to_target = target.translation.xy() - position;
+        if to_target == Vec2::ZERO {
+            continue;
+        }
+
+        let wanted = bullet.velocity.angle_to(to_target);
+        let max_turn = homing.turn_rate * time.delta_secs();
+        bullet.velocity =
+            Vec2::from_angle(wanted.clamp(-max_turn, max_turn)).rotate(bullet.velocity);
+        transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_to(bullet.velocity));
     }
 }
 
@@ -77,13 +189,12 @@ fn bullet_movement(
     time: Res<Time>,
 ) {
     for (entity, mut transform, bullet) in &mut query {
-        if bullet.is_player {
-            transform.translation.y += bullet.speed * time.delta_secs();
-        } else {
-            transform.translation.y -= bullet.speed * time.delta_secs();
-        }
+        transform.translation += (bullet.velocity * time.delta_secs()).extend(0.0);
 
-        if transform.translation.y >= 360.0 || transform.translation.y < -360.0 {
+        if transform.translation.y >= playfield::HALF_SIZE.y
+            || transform.translation.y < -playfield::HALF_SIZE.y
+            || transform.translation.x.abs() > playfield::HALF_SIZE.x + SIDE_MARGIN
+        {
             commands.entity(entity).despawn();
         }
     }
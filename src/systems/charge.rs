@@ -0,0 +1,154 @@
+use crate::GameState;
+use crate::PlayState;
+use crate::components::assets::*;
+use crate::components::bullet::*;
+use crate::components::input::Action;
+use crate::components::player::Player;
+use crate::components::weapon::*;
+use crate::systems::bullet::{bullet_spawn, spawn_player_bullet};
+use crate::systems::input::Actions;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+pub struct ChargePlugin;
+
+impl Plugin for ChargePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChargeShot>()
+            .add_systems(
+                Update,
+                update_charge
+                    .before(bullet_spawn)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                update_charge_glow
+                    .after(update_charge)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(OnEnter(PlayState::Paused), drop_charge)
+            .add_systems(OnExit(GameState::Playing), cleanup_charge);
+    }
+}
+
+fn update_charge(
+    mut commands: Commands,
+    actions: Actions,
+    mut charge: ResMut<ChargeShot>,
+    query: Query<(&Transform, &Player)>,
+    time: Res<Time>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+    assets: Res<GameAssets>,
+) {
+    let Ok((transform, player)) = query.single() else {
+        *charge = ChargeShot::default();
+        return;
+    };
+    // The laser is already hold-to-fire, so it never charges.
+    if player.weapon.kind == WeaponKind::Laser {
+        *charge = ChargeShot::default();
+        return;
+    }
+
+    if actions.pressed(Action::Fire) {
+        let was_charging = charge.is_charging();
+        charge.held += time.delta_secs();
+
+        if charge.is_charging() && !was_charging {
+            audio
+                .play(assets.shoot_sound.clone())
+                .with_volume(0.2)
+                .with_playback_rate(0.5);
+        }
+        if charge.level() >= 1.0 && !charge.full_cue_played {
+            charge.full_cue_played = true;
+            audio
+                .play(assets.shoot_sound.clone())
+                .with_volume(0.3)
+                .with_playback_rate(1.5);
+        }
+        return;
+    }
+
+    // Until the charge shows, the normal gun kept firing, so there is nothing to release.
+    let charged = charge.is_charging();
+    let level = charge.level();
+    *charge = ChargeShot::default();
+    if !charged {
+        return;
+    }
+
+    let shot = Weapon::charge_shot(player.bullet_damage, level);
+    spawn_player_bullet(
+        &mut commands,
+        transform.translation.xy() + Vec2::new(0.0, 30.0) + shot.offset,
+        &shot,
+        Color::WHITE,
+        true,
+    );
+    audio
+        .play(assets.explosion_sound.clone())
+        .with_volume(0.3)
+        .with_playback_rate(1.5);
+}
+
+/// Pausing drops the charge, so letting go of Fire in the pause menu does not fire a
+/// charge shot on resume.
+fn drop_charge(mut charge: ResMut<ChargeShot>) {
+    *charge = ChargeShot::default();
+}
+
+/// Grows a glow at the muzzle with the charge, pulsing once it is full.
+fn update_charge_glow(
+    mut commands: Commands,
+    charge: Res<ChargeShot>,
+    player_query: Query<&Transform, (With<Player>, Without<ChargeGlow>)>,
+    mut glow_query: Query<(Entity, &mut Transform), With<ChargeGlow>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+) {
+    let player = player_query.single().ok().filter(|_| charge.is_charging());
+    let Some(player_transform) = player else {
+        for (entity, _) in &glow_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let level = charge.level();
+    let pulse = if level >= 1.0 {
+        1.0 + 0.15 * (time.elapsed_secs() * 20.0).sin()
+    } else {
+        1.0
+    };
+    let radius = (4.0 + 12.0 * level) * pulse;
+    let translation = player_transform.translation + Vec3::new(0.0, 30.0, 1.0);
+
+    match glow_query.single_mut() {
+        Ok((_, mut transform)) => {
+            transform.translation = translation;
+            transform.scale = Vec3::splat(radius);
+        }
+        Err(_) => {
+            commands.spawn((
+                Mesh2d(meshes.add(Circle::new(1.0))),
+                MeshMaterial2d(materials.add(Color::srgba(1.0, 1.0, 1.0, 0.7))),
+                Transform::from_translation(translation).with_scale(Vec3::splat(radius)),
+                ChargeGlow,
+            ));
+        }
+    }
+}
+
+fn cleanup_charge(
+    mut commands: Commands,
+    mut charge: ResMut<ChargeShot>,
+    query: Query<Entity, With<ChargeGlow>>,
+) {
+    *charge = ChargeShot::default();
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
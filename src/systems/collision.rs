@@ -1,227 +1,298 @@
-use crate::GameState;
+use crate::PlayState;
 use crate::components::assets::*;
+use crate::components::boss::BossPart;
+use crate::components::broadphase::SpatialGrid;
+use crate::components::bullet::Bullet;
 use crate::components::collider::*;
+use crate::components::enemy::{Enemy, EnemyDefeated, Health, HitFlash};
 use crate::components::explosion::*;
-use crate::components::item::*;
+use crate::components::formation::FormationMember;
 use crate::components::player::*;
-use crate::components::score::Score;
-use crate::components::stage::*;
-use crate::components::wave::*;
+use crate::components::weapon::LaserBeam;
 use crate::systems::explosion::spawn_explosion;
-use crate::systems::item::*;
+use bevy::ecs::query::QueryItem;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
-use rand::Rng;
+use std::collections::HashSet;
 
 pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            collision_system.run_if(in_state(GameState::Playing)),
-        );
+        app.init_resource::<SpatialGrid>()
+            .add_event::<CollisionEvent>()
+            .add_event::<PlayerDamaged>()
+            .add_event::<EnemyDefeated>()
+            .add_systems(
+                Update,
+                collision_system.run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                apply_collision_damage
+                    .after(collision_system)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                damage_feedback
+                    .after(apply_collision_damage)
+                    .run_if(in_state(PlayState::Running)),
+            );
     }
 }
 
 pub fn collision_system(
     query: Query<(Entity, &Transform, &Collider)>,
-    mut player_query: Query<&mut Player>,
-    item_query: Query<&mut ItemType>,
+    mut grid: ResMut<SpatialGrid>,
+    mut events: EventWriter<CollisionEvent>,
+) {
+    let colliders: Vec<(Entity, &Transform, &Collider, WorldShape)> = query
+        .iter()
+        .map(|(entity, transform, collider)| {
+            (entity, transform, collider, collider.world_shape(transform))
+        })
+        .collect();
+    grid.rebuild(colliders.iter().map(|(.., shape)| *shape));
+
+    for (i, j) in grid.pairs() {
+        let (e1, _, c1, s1) = colliders[i];
+        let (e2, _, c2, s2) = colliders[j];
+
+        if c1.interacts_with(c2) && s1.overlaps(&s2) {
+            events.write(CollisionEvent {
+                a: e1,
+                b: e2,
+                kinds: (c1.layer, c2.layer),
+            });
+        }
+    }
+}
+
+type EnemyTargetData = (
+    &'static mut Health,
+    &'static Transform,
+    Option<&'static FormationMember>,
+);
+
+/// Lookups that decide who a collision really hurts and by how much.
+#[derive(SystemParam)]
+pub struct HitLookups<'w, 's> {
+    lasers: Query<'w, 's, &'static LaserBeam>,
+    parts: Query<'w, 's, &'static BossPart>,
+    cores: Query<'w, 's, &'static PlayerCore>,
+}
+
+/// Events raised when a hit lands.
+#[derive(SystemParam)]
+pub struct DamageEvents<'w> {
+    player_damaged: EventWriter<'w, PlayerDamaged>,
+    enemy_defeated: EventWriter<'w, EnemyDefeated>,
+}
+
+pub fn apply_collision_damage(
+    mut events: EventReader<CollisionEvent>,
+    mut player_query: Query<(&mut Player, &Transform)>,
+    mut enemy_query: Query<EnemyTargetData, With<Enemy>>,
+    mut bullet_query: Query<&mut Bullet>,
+    lookups: HitLookups,
     mut commands: Commands,
-    assets: Res<GameAssets>,
-    audio: Res<bevy_kira_audio::prelude::Audio>,
-    mut score: ResMut<Score>,
-    mut stage_db: ResMut<StageDatabase>,
-    //mut waves: ResMut<Waves>,
+    mut damage_events: DamageEvents,
 ) {
-    let mut pairs = query.iter_combinations::<2>();
+    // A beam touching a boss and its parts resolves to the boss several times, but should
+    // only hurt it once per frame.
+    let mut beam_hits: HashSet<(Entity, Entity)> = HashSet::new();
 
-    while let Some([(e1, t1, c1), (e2, t2, c2)]) = pairs.fetch_next() {
-        let collision = match (&c1.shape, &c2.shape) {
-            (ColliderShape::Circle { radius: r1 }, ColliderShape::Circle { radius: r2 }) => {
-                let dist = t1.translation.distance(t2.translation);
-                dist < (r1 + r2)
-            }
-            (ColliderShape::Rectangle { size: s1 }, ColliderShape::Rectangle { size: s2 })
-            | (ColliderShape::Capsule { size: s1 }, ColliderShape::Capsule { size: s2 }) => {
-                let pos1 = t1.translation.xy();
-                let pos2 = t2.translation.xy();
-                let half1 = *s1 / 2.0;
-                let half2 = *s2 / 2.0;
-
-                (pos1.x - pos2.x).abs() < (half1.x + half2.x)
-                    && (pos1.y - pos2.y).abs() < (half1.y + half2.y)
-            }
-            _ => false,
-        };
-
-        if collision {
-            match (c1.tag, c2.tag) {
-                (ColliderTag::Player, ColliderTag::Enemy)
-                | (ColliderTag::Player, ColliderTag::EnemyBullet) => {
-                    handle_player_enemy_collision(
+    for event in events.read() {
+        if let Some((player, other)) = event.between(
+            CollisionLayers::PLAYER,
+            CollisionLayers::ENEMY | CollisionLayers::ENEMY_BULLET,
+        ) {
+            // The core hitbox forwards hits to the player it belongs to.
+            let player = match lookups.cores.get(player) {
+                Ok(core) => core.player,
+                Err(_) => player,
+            };
+            let damage = match bullet_query.get(other) {
+                Ok(bullet) => bullet.damage as i32,
+                Err(_) => 1,
+            };
+            damage_player(
+                &mut commands,
+                &mut player_query,
+                &mut damage_events.player_damaged,
+                player,
+                damage,
+            );
+        } else if let Some((enemy, bullet)) =
+            event.between(CollisionLayers::ENEMY, CollisionLayers::PLAYER_BULLET)
+        {
+            if let Ok(beam) = lookups.lasers.get(bullet) {
+                // Beams deal damage over time, so every frame of contact counts.
+                if let Some((enemy, (mut health, transform, member))) =
+                    resolve_enemy(&mut enemy_query, &lookups.parts, enemy)
+                {
+                    if !beam_hits.insert((bullet, enemy)) {
+                        continue;
+                    }
+                    hurt_enemy(
                         &mut commands,
-                        &mut player_query,
-                        &assets,
-                        &audio,
-                        e1.clone(),
-                        t1.clone(),
+                        &mut damage_events.enemy_defeated,
+                        enemy,
+                        &mut health,
+                        beam.damage,
+                        EnemyDefeated {
+                            position: transform.translation,
+                            formation: member.map(|member| member.leader),
+                        },
                     );
                 }
-                (ColliderTag::Enemy, ColliderTag::Player)
-                | (ColliderTag::EnemyBullet, ColliderTag::Player) => {
-                    handle_player_enemy_collision(
-                        &mut commands,
-                        &mut player_query,
-                        &assets,
-                        &audio,
-                        e2.clone(),
-                        t2.clone(),
-                    );
-                }
-                (ColliderTag::Enemy, ColliderTag::Bullet) => {
-                    handle_enemy_bullet_collision(
-                        &mut commands,
-                        &mut player_query,
-                        &assets,
-                        &audio,
-                        &mut score,
-                        &mut stage_db,
-                        //&mut waves,
-                        e1.clone(),
-                        e2.clone(),
-                        t1.clone(),
-                    );
-                }
-                (ColliderTag::Bullet, ColliderTag::Enemy) => {
-                    handle_enemy_bullet_collision(
-                        &mut commands,
-                        &mut player_query,
-                        &assets,
-                        &audio,
-                        &mut score,
-                        &mut stage_db,
-                        //&mut waves,
-                        e2.clone(),
-                        e1.clone(),
-                        t2.clone(),
-                    );
-                }
-                (ColliderTag::Player, ColliderTag::Item) => {
-                    handle_player_item_collision(
-                        &mut commands,
-                        &item_query,
-                        &mut player_query,
-                        e1,
-                        e2,
-                    );
-                }
-                (ColliderTag::Item, ColliderTag::Player) => {
-                    handle_player_item_collision(
-                        &mut commands,
-                        &item_query,
-                        &mut player_query,
-                        e2,
-                        e1,
-                    );
-                }
-                _ => {}
+                continue;
             }
+
+            damage_enemy(
+                &mut commands,
+                &mut enemy_query,
+                &mut bullet_query,
+                &lookups.parts,
+                &mut damage_events.enemy_defeated,
+                enemy,
+                bullet,
+            );
         }
     }
 }
 
-fn handle_player_enemy_collision(
+fn damage_player(
     commands: &mut Commands,
-    player_query: &mut Query<&mut Player>,
-    assets: &Res<GameAssets>,
-    audio: &Res<bevy_kira_audio::prelude::Audio>,
+    player_query: &mut Query<(&mut Player, &Transform)>,
+    player_damaged: &mut EventWriter<PlayerDamaged>,
     player_entity: Entity,
-    player_transform: Transform,
+    damage: i32,
 ) {
-    if let Ok(mut player) = player_query.get_mut(player_entity) {
-        if player.invincible_timer.finished() {
-            player.hp -= 1;
-            player.invincible_timer = Timer::from_seconds(2.0, TimerMode::Once);
-
-            if player.hp <= 0 {
-                spawn_explosion(
-                    commands,
-                    player_transform.translation.clone(),
-                    &assets,
-                    ExplosionTag::Player,
-                );
-                audio.play(assets.explosion_sound.clone()).with_volume(0.2);
-                commands.entity(player_entity).despawn();
-            } else {
-                audio.play(assets.damage_sound.clone()).with_volume(0.2);
-            }
-        }
+    let Ok((mut player, transform)) = player_query.get_mut(player_entity) else {
+        return;
+    };
+    if !player.invincible_timer.finished() {
+        return;
+    }
+
+    player.hp -= damage;
+    player.invincible_timer = Timer::from_seconds(2.0, TimerMode::Once);
+
+    let killed = player.hp <= 0;
+    if killed {
+        commands.entity(player_entity).despawn();
     }
+    player_damaged.write(PlayerDamaged {
+        position: transform.translation,
+        killed,
+    });
 }
 
-fn handle_enemy_bullet_collision(
+fn damage_enemy(
     commands: &mut Commands,
-    player_query: &mut Query<&mut Player>,
-    assets: &Res<GameAssets>,
-    audio: &Res<bevy_kira_audio::prelude::Audio>,
-    score: &mut ResMut<Score>,
-    stage_db: &mut ResMut<StageDatabase>,
-    //waves: &mut ResMut<Waves>,
+    enemy_query: &mut Query<EnemyTargetData, With<Enemy>>,
+    bullet_query: &mut Query<&mut Bullet>,
+    part_query: &Query<&BossPart>,
+    enemy_defeated: &mut EventWriter<EnemyDefeated>,
     enemy_entity: Entity,
     bullet_entity: Entity,
-    enemy_transform: Transform,
 ) {
-    if let Ok(player) = player_query.single() {
-        if !player.piercing {
-            commands.entity(bullet_entity).despawn();
-        }
+    let Some((enemy_entity, (mut health, transform, member))) =
+        resolve_enemy(enemy_query, part_query, enemy_entity)
+    else {
+        return;
+    };
+    let Ok(mut bullet) = bullet_query.get_mut(bullet_entity) else {
+        return;
+    };
+
+    // Skip enemies already killed this frame and bullets that already hit something.
+    if health.current == 0 || bullet.hits.contains(&enemy_entity) {
+        return;
     }
-    commands.entity(enemy_entity).despawn();
-    spawn_explosion(
+    if !bullet.piercing && !bullet.hits.is_empty() {
+        return;
+    }
+
+    bullet.hits.push(enemy_entity);
+    if !bullet.piercing {
+        commands.entity(bullet_entity).despawn();
+    }
+
+    hurt_enemy(
         commands,
-        enemy_transform.translation.clone(),
-        &assets,
-        ExplosionTag::Enemy,
+        enemy_defeated,
+        enemy_entity,
+        &mut health,
+        bullet.damage,
+        EnemyDefeated {
+            position: transform.translation,
+            formation: member.map(|member| member.leader),
+        },
     );
-    audio.play(assets.explosion_sound.clone()).with_volume(0.2);
-    score.score += 100;
-    //let current_wave = waves.current_wave;
-    //waves.waves[current_wave].defeated_count += 1;
-    let stage_index = stage_db.current_index.clone();
-    let wave_index = stage_db.settings[stage_index].current_index;
-    stage_db.settings[stage_index].waves[wave_index].defeated_count += 1;
-
-    let mut rng = rand::rng();
-    let value = rng.random_range(0..100);
-    if value < 30 {
-        let item_type = match rng.random_range(0..3) {
-            0 => ItemType::RapidFire,
-            1 => ItemType::PiercingShot,
-            2 => ItemType::Heal,
-            _ => ItemType::RapidFire,
-        };
-        spawn_item(
-            commands,
-            &assets,
-            item_type,
-            enemy_transform.translation.clone(),
-        );
-    }
 }
 
-fn handle_player_item_collision(
+/// Boss parts forward hits to the boss itself.
+fn resolve_enemy<'a>(
+    enemy_query: &'a mut Query<EnemyTargetData, With<Enemy>>,
+    part_query: &Query<&BossPart>,
+    enemy_entity: Entity,
+) -> Option<(Entity, QueryItem<'a, EnemyTargetData>)> {
+    let enemy_entity = match part_query.get(enemy_entity) {
+        Ok(part) => part.boss,
+        Err(_) => enemy_entity,
+    };
+    enemy_query
+        .get_mut(enemy_entity)
+        .ok()
+        .map(|enemy| (enemy_entity, enemy))
+}
+
+fn hurt_enemy(
     commands: &mut Commands,
-    item_query: &Query<&mut ItemType>,
-    player_query: &mut Query<&mut Player>,
-    player_entity: Entity,
-    item_entity: Entity,
+    enemy_defeated: &mut EventWriter<EnemyDefeated>,
+    enemy_entity: Entity,
+    health: &mut Health,
+    damage: u32,
+    defeated: EnemyDefeated,
 ) {
-    if let Ok(item) = item_query.get(item_entity) {
-        if let Ok(mut player) = player_query.get_mut(player_entity) {
-            apply_item_effect(&mut player, *item);
-            commands.entity(item_entity).despawn();
+    if health.current == 0 || damage == 0 {
+        return;
+    }
+
+    health.current = health.current.saturating_sub(damage);
+    if health.current > 0 {
+        commands
+            .entity(enemy_entity)
+            .try_insert(HitFlash::default());
+        return;
+    }
+
+    commands.entity(enemy_entity).despawn();
+    enemy_defeated.write(defeated);
+}
+
+fn damage_feedback(
+    mut commands: Commands,
+    mut player_damaged: EventReader<PlayerDamaged>,
+    mut enemy_defeated: EventReader<EnemyDefeated>,
+    assets: Res<GameAssets>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+) {
+    for event in player_damaged.read() {
+        if event.killed {
+            spawn_explosion(&mut commands, event.position, &assets, ExplosionTag::Player);
+            audio.play(assets.explosion_sound.clone()).with_volume(0.2);
+        } else {
+            audio.play(assets.damage_sound.clone()).with_volume(0.2);
         }
     }
+
+    for event in enemy_defeated.read() {
+        spawn_explosion(&mut commands, event.position, &assets, ExplosionTag::Enemy);
+        audio.play(assets.explosion_sound.clone()).with_volume(0.2);
+    }
 }
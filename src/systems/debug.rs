@@ -0,0 +1,160 @@
+use crate::components::collider::*;
+use crate::components::stage::StageDatabase;
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+
+/// Collider outlines and a stats panel, toggled with F3.
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin::default());
+        }
+        app.init_resource::<DebugOverlay>()
+            .add_systems(Update, toggle_debug_overlay)
+            .add_systems(
+                Update,
+                (draw_colliders, update_debug_text)
+                    .after(toggle_debug_overlay)
+                    .run_if(|overlay: Res<DebugOverlay>| overlay.enabled),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+struct DebugText;
+
+const LAYERS: [(CollisionLayers, &str); 5] = [
+    (CollisionLayers::PLAYER, "player"),
+    (CollisionLayers::ENEMY, "enemy"),
+    (CollisionLayers::PLAYER_BULLET, "player bullet"),
+    (CollisionLayers::ENEMY_BULLET, "enemy bullet"),
+    (CollisionLayers::ITEM, "item"),
+];
+
+fn layer_color(layer: CollisionLayers) -> Color {
+    match layer {
+        CollisionLayers::PLAYER => Color::srgb(0.2, 1.0, 0.2),
+        CollisionLayers::ENEMY => Color::srgb(1.0, 0.2, 0.2),
+        CollisionLayers::PLAYER_BULLET => Color::srgb(0.2, 0.8, 1.0),
+        CollisionLayers::ENEMY_BULLET => Color::srgb(1.0, 0.7, 0.1),
+        CollisionLayers::ITEM => Color::srgb(1.0, 0.3, 1.0),
+        _ => Color::WHITE,
+    }
+}
+
+fn toggle_debug_overlay(
+    mut commands: Commands,
+    mut overlay: ResMut<DebugOverlay>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    text_query: Query<Entity, With<DebugText>>,
+) {
+    if !keyboard.just_pressed(KeyCode::F3) {
+        return;
+    }
+
+    overlay.enabled = !overlay.enabled;
+    if !overlay.enabled {
+        for entity in &text_query {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn draw_colliders(mut gizmos: Gizmos, query: Query<(&Transform, &Collider)>) {
+    for (transform, collider) in &query {
+        let color = layer_color(collider.layer);
+
+        match collider.world_shape(transform) {
+            WorldShape::Circle { center, radius } => {
+                gizmos.circle_2d(center, radius, color);
+            }
+            WorldShape::Box {
+                center,
+                half_size,
+                axis,
+            } => {
+                let rotation = Rot2::from_sin_cos(axis.y, axis.x);
+                gizmos.rect_2d(Isometry2d::new(center, rotation), half_size * 2.0, color);
+            }
+            WorldShape::Capsule { a, b, radius } => {
+                // Capsule2d runs along its local y axis.
+                let direction = b - a;
+                let rotation = Rot2::radians(direction.to_angle() - std::f32::consts::FRAC_PI_2);
+                gizmos.primitive_2d(
+                    &Capsule2d::new(radius, direction.length()),
+                    Isometry2d::new((a + b) / 2.0, rotation),
+                    color,
+                );
+            }
+        }
+    }
+}
+
+fn update_debug_text(
+    mut commands: Commands,
+    mut text_query: Query<&mut Text, With<DebugText>>,
+    collider_query: Query<&Collider>,
+    diagnostics: Res<DiagnosticsStore>,
+    stage_db: Option<Res<StageDatabase>>,
+) {
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+
+    let mut lines = vec![format!("fps: {:.0}", fps)];
+
+    let current_stage = stage_db.as_deref().and_then(|stage_db| {
+        let stage = stage_db.settings.get(stage_db.current_index)?;
+        Some((stage_db, stage))
+    });
+    if let Some((stage_db, stage)) = current_stage {
+        lines.push(format!(
+            "stage: {}/{}  wave: {}/{}",
+            stage_db.current_index + 1,
+            stage_db.settings.len(),
+            stage.current_index + 1,
+            stage.waves.len(),
+        ));
+    }
+
+    lines.push(format!("colliders: {}", collider_query.iter().count()));
+    for (layer, name) in LAYERS {
+        let count = collider_query
+            .iter()
+            .filter(|collider| collider.layer == layer)
+            .count();
+        lines.push(format!("  {}: {}", name, count));
+    }
+
+    let report = lines.join("\n");
+    match text_query.single_mut() {
+        Ok(mut text) => text.0 = report,
+        // Menus clear every text on exit, so put the panel back when it goes missing.
+        Err(_) => {
+            commands.spawn((
+                Text::new(report),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.6, 1.0, 0.6)),
+                Node {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(8.0),
+                    left: Val::Px(8.0),
+                    ..default()
+                },
+                GlobalZIndex(100),
+                DebugText,
+            ));
+        }
+    }
+}
@@ -1,35 +1,72 @@
 use crate::GameState;
-use crate::components::assets::AssetVisual;
+use crate::PlayState;
 use crate::components::assets::*;
-use crate::components::bullet::Bullet;
-use crate::components::bullet::BulletDefinition;
+use crate::components::bullet::*;
 use crate::components::collider::*;
 use crate::components::enemy::*;
+use crate::components::formation::*;
 use crate::components::player::Player;
+use crate::components::playfield;
 use crate::components::stage::StageDatabase;
+use crate::components::wave::*;
+use crate::systems::assets::{load_visual, sprite_from_visual};
+use crate::systems::collision::apply_collision_damage;
+use bevy::asset::{LoadedFolder, RecursiveDependencyLoadState};
 use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
 use rand::Rng;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Playing), initialize_spawn_timer)
-            .add_systems(OnEnter(GameState::Playing), load_enemies)
+        app.add_plugins(RonAssetPlugin::<EnemyAsset>::new(&["enemy.ron"]))
+            .add_event::<FormationCleared>()
+            .add_systems(OnEnter(GameState::Playing), initialize_spawn_timer)
             .add_systems(
                 Update,
-                spawn_enemy_from_kind.run_if(in_state(GameState::Playing)),
+                reload_enemies.run_if(resource_exists::<EnemyDatabase>),
             )
-            .add_systems(Update, enemy_movement.run_if(in_state(GameState::Playing)))
             .add_systems(
                 Update,
-                enemy_fire_system.run_if(in_state(GameState::Playing)),
+                load_enemies.run_if(
+                    in_state(GameState::StageSelect).and(not(resource_exists::<EnemyDatabase>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                spawn_enemy_from_kind.run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                spawn_timeline_enemies.run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(Update, enemy_movement.run_if(in_state(PlayState::Running)))
+            .add_systems(
+                Update,
+                formation_system
+                    .after(enemy_movement)
+                    .after(apply_collision_damage)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                hit_flash_system.run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                enemy_fire_system.run_if(in_state(PlayState::Running)),
             )
             .add_systems(OnExit(GameState::Playing), cleanup_enemies);
     }
 }
 
+/// How far inside the top and side edges of the playfield random enemies spawn.
+const SPAWN_MARGIN: Vec2 = Vec2::new(30.0, 20.0);
+/// How far below the playfield enemies fall before they are removed.
+const DESPAWN_MARGIN: f32 = 20.0;
+
 fn initialize_spawn_timer(mut commands: Commands, stage_db: Res<StageDatabase>) {
     let stage_index = stage_db.current_index;
     let wave_index = stage_db.settings[stage_index].current_index;
@@ -40,67 +77,248 @@ fn initialize_spawn_timer(mut commands: Commands, stage_db: Res<StageDatabase>)
     });
 }
 
-fn load_enemies(mut commands: Commands, assets: Res<GameAssets>) {
-    let enemy_db = EnemyDatabase {
-        defs: HashMap::from([
-            (
-                EnemyKind::DinoStraight,
-                EnemyDefinition {
-                    kind: EnemyKind::DinoStraight,
-                    max_hp: 3,
-                    movement_pattern: EnemyMovePattern::Straight,
-                    collider_size: Vec2::new(30.0, 30.0),
-                    fire_interval: 1.0,
-                    bullet: Some(BulletDefinition {
-                        is_player: false,
-                        speed: 400.0,
-                        damage: 1,
-                        collider_size: Vec2::new(30.0, 30.0),
-                        visual: AssetVisual::Static(assets.blue_egg_texture.clone()),
-                    }),
-                    visual: AssetVisual::Animated(assets.dino_assets.clone()),
-                },
-            ),
-            (
-                EnemyKind::DinoZigzag,
-                EnemyDefinition {
-                    kind: EnemyKind::DinoZigzag,
-                    max_hp: 3,
-                    movement_pattern: EnemyMovePattern::Zigzag,
-                    collider_size: Vec2::new(30.0, 30.0),
-                    fire_interval: 1.0,
-                    bullet: Some(BulletDefinition {
-                        is_player: false,
-                        speed: 400.0,
-                        damage: 1,
-                        collider_size: Vec2::new(30.0, 30.0),
-                        visual: AssetVisual::Static(assets.blue_egg_texture.clone()),
-                    }),
-                    visual: AssetVisual::Animated(assets.dino_assets.clone()),
-                },
-            ),
-            (
-                EnemyKind::DinoHoming,
-                EnemyDefinition {
-                    kind: EnemyKind::DinoHoming,
-                    max_hp: 3,
-                    movement_pattern: EnemyMovePattern::Homing,
-                    collider_size: Vec2::new(30.0, 30.0),
-                    fire_interval: 1.0,
-                    bullet: Some(BulletDefinition {
-                        is_player: false,
-                        speed: 400.0,
-                        damage: 1,
-                        collider_size: Vec2::new(30.0, 30.0),
-                        visual: AssetVisual::Static(assets.blue_egg_texture.clone()),
-                    }),
-                    visual: AssetVisual::Animated(assets.dino_assets.clone()),
-                },
+fn load_enemies(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    asset_server: Res<AssetServer>,
+    folders: Res<Assets<LoadedFolder>>,
+    enemy_assets: Res<Assets<EnemyAsset>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    match asset_server.recursive_dependency_load_state(&assets.enemy_folder) {
+        RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_) => {}
+        _ => return,
+    }
+
+    let Some(folder) = folders.get(&assets.enemy_folder) else {
+        return;
+    };
+
+    let mut defs: HashMap<EnemyKind, EnemyDefinition> = HashMap::new();
+    for (handle, enemy) in enemies_in_folder(folder, &enemy_assets) {
+        match build_enemy_definition(enemy, &asset_server, &mut texture_atlas_layouts, None) {
+            Ok(enemy_def) => {
+                defs.insert(enemy_def.kind.clone(), enemy_def);
+            }
+            Err(e) => error!("skipping {:?}: {}", handle.path(), e),
+        }
+    }
+
+    if defs.is_empty() {
+        error!("no enemy definition found in assets/enemies");
+    }
+
+    commands.insert_resource(EnemyDatabase { defs });
+}
+
+/// The enemy assets of the folder, keeping only the first file for each id.
+fn enemies_in_folder<'a>(
+    folder: &LoadedFolder,
+    enemy_assets: &'a Assets<EnemyAsset>,
+) -> Vec<(Handle<EnemyAsset>, &'a EnemyAsset)> {
+    let mut enemies: Vec<(Handle<EnemyAsset>, &EnemyAsset)> = Vec::new();
+    for handle in folder.handles.iter() {
+        let Ok(handle) = handle.clone().try_typed::<EnemyAsset>() else {
+            continue;
+        };
+        let Some(enemy) = enemy_assets.get(&handle) else {
+            continue;
+        };
+
+        if enemies.iter().any(|(_, other)| other.id == enemy.id) {
+            warn!(
+                "duplicate enemy id \"{}\" in {:?}, keeping the first one",
+                enemy.id,
+                handle.path()
+            );
+            continue;
+        }
+        enemies.push((handle, enemy));
+    }
+    enemies
+}
+
+/// `previous` is the definition being replaced on a reload. Its atlas layouts are reused
+/// so that reloading does not add new ones every time.
+fn build_enemy_definition(
+    enemy: &EnemyAsset,
+    asset_server: &AssetServer,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    previous: Option<&EnemyDefinition>,
+) -> Result<EnemyDefinition, EnemyLoadError> {
+    // Both values go straight into repeating timers, which panic on negative durations
+    // and fire every frame on zero.
+    if enemy.bullet.is_some() && enemy.fire_interval <= 0.0 {
+        return Err(EnemyLoadError::InvalidFireInterval {
+            id: enemy.id.clone(),
+            value: enemy.fire_interval,
+        });
+    }
+    if let Some(value) = enemy.bullet_pattern.invalid_burst_delay() {
+        return Err(EnemyLoadError::InvalidBurstDelay {
+            id: enemy.id.clone(),
+            value,
+        });
+    }
+
+    let previous_bullet = previous
+        .and_then(|previous| previous.bullet.as_ref())
+        .map(|bullet| &bullet.visual);
+    Ok(EnemyDefinition {
+        kind: EnemyKind(enemy.id.clone()),
+        max_hp: enemy.max_hp,
+        movement_pattern: enemy.movement_pattern.clone(),
+        collider_size: Vec2::from(enemy.collider_size),
+        fire_interval: enemy.fire_interval,
+        bullet: enemy.bullet.as_ref().map(|bullet| BulletDefinition {
+            speed: bullet.speed,
+            damage: bullet.damage,
+            collider_size: Vec2::from(bullet.collider_size),
+            visual: load_visual(
+                &bullet.visual,
+                asset_server,
+                texture_atlas_layouts,
+                previous_bullet,
             ),
-        ]),
+        }),
+        bullet_pattern: enemy.bullet_pattern.clone(),
+        visual: load_visual(
+            &enemy.visual,
+            asset_server,
+            texture_atlas_layouts,
+            previous.map(|previous| &previous.visual),
+        ),
+    })
+}
+
+fn reload_enemies(
+    mut events: EventReader<AssetEvent<EnemyAsset>>,
+    assets: Res<GameAssets>,
+    asset_server: Res<AssetServer>,
+    folders: Res<Assets<LoadedFolder>>,
+    enemy_assets: Res<Assets<EnemyAsset>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut enemy_db: ResMut<EnemyDatabase>,
+) {
+    let modified: HashSet<AssetId<EnemyAsset>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    if modified.is_empty() {
+        return;
+    }
+    let Some(folder) = folders.get(&assets.enemy_folder) else {
+        return;
     };
 
-    commands.insert_resource(enemy_db);
+    // Rebuild from the whole folder, so that an id renamed in a file does not leave its
+    // old entry behind. Spawned enemies copied what they need at spawn time, so only new
+    // spawns pick up the reloaded definitions.
+    let mut defs: HashMap<EnemyKind, EnemyDefinition> = HashMap::new();
+    for (handle, enemy) in enemies_in_folder(folder, &enemy_assets) {
+        let kind = EnemyKind(enemy.id.clone());
+        let previous = enemy_db.defs.get(&kind);
+        let enemy_def = match build_enemy_definition(
+            enemy,
+            &asset_server,
+            &mut texture_atlas_layouts,
+            previous,
+        ) {
+            Ok(enemy_def) => enemy_def,
+            Err(e) => {
+                error!("keeping previous version of enemy: {}", e);
+                if let Some(previous) = previous {
+                    defs.insert(kind, previous.clone());
+                }
+                continue;
+            }
+        };
+
+        if modified.contains(&handle.id()) {
+            match previous {
+                Some(old) => {
+                    let changes = describe_enemy_changes(old, &enemy_def);
+                    if changes.is_empty() {
+                        info!("reloaded enemy \"{}\": visuals only", enemy.id);
+                    } else {
+                        info!("reloaded enemy \"{}\": {}", enemy.id, changes.join(", "));
+                    }
+                }
+                None => info!("reloaded enemy \"{}\": new definition", enemy.id),
+            }
+        }
+        defs.insert(kind, enemy_def);
+    }
+
+    for kind in enemy_db.defs.keys() {
+        if !defs.contains_key(kind) {
+            info!("enemy \"{}\" is no longer defined", kind.0);
+        }
+    }
+    enemy_db.defs = defs;
+}
+
+fn describe_enemy_changes(old: &EnemyDefinition, new: &EnemyDefinition) -> Vec<String> {
+    let mut changes: Vec<String> = Vec::new();
+
+    if old.max_hp != new.max_hp {
+        changes.push(format!("max_hp {} -> {}", old.max_hp, new.max_hp));
+    }
+    if old.movement_pattern != new.movement_pattern {
+        changes.push(format!(
+            "movement_pattern {:?} -> {:?}",
+            old.movement_pattern, new.movement_pattern
+        ));
+    }
+    if old.collider_size != new.collider_size {
+        changes.push(format!(
+            "collider_size {} -> {}",
+            old.collider_size, new.collider_size
+        ));
+    }
+    if old.fire_interval != new.fire_interval {
+        changes.push(format!(
+            "fire_interval {} -> {}",
+            old.fire_interval, new.fire_interval
+        ));
+    }
+
+    match (&old.bullet, &new.bullet) {
+        (Some(old_bullet), Some(new_bullet)) => {
+            if old_bullet.speed != new_bullet.speed {
+                changes.push(format!(
+                    "bullet.speed {} -> {}",
+                    old_bullet.speed, new_bullet.speed
+                ));
+            }
+            if old_bullet.damage != new_bullet.damage {
+                changes.push(format!(
+                    "bullet.damage {} -> {}",
+                    old_bullet.damage, new_bullet.damage
+                ));
+            }
+            if old_bullet.collider_size != new_bullet.collider_size {
+                changes.push(format!(
+                    "bullet.collider_size {} -> {}",
+                    old_bullet.collider_size, new_bullet.collider_size
+                ));
+            }
+        }
+        (None, Some(_)) => changes.push(String::from("bullet added")),
+        (Some(_), None) => changes.push(String::from("bullet removed")),
+        (None, None) => {}
+    }
+    if old.bullet_pattern != new.bullet_pattern {
+        changes.push(format!(
+            "bullet_pattern {:?} -> {:?}",
+            old.bullet_pattern, new.bullet_pattern
+        ));
+    }
+
+    changes
 }
 
 fn spawn_enemy_from_kind(
@@ -122,11 +340,17 @@ fn spawn_enemy_from_kind(
         return;
     }
 
-    let mut rng = rand::rng();
-    let x = rng.random_range(-210.0..210.0);
-
     let stage_id = stage_db.current_index;
     let wave_id = stage_db.settings[stage_id].current_index;
+    if stage_db.settings[stage_id].waves[wave_id].mode != WaveMode::Random {
+        return;
+    }
+
+    let mut rng = rand::rng();
+    let spawn_x = playfield::HALF_SIZE.x - SPAWN_MARGIN.x;
+    let x = rng.random_range(-spawn_x..spawn_x);
+    let y = playfield::HALF_SIZE.y - SPAWN_MARGIN.y;
+
     let distribution = stage_db.settings[stage_id].waves[wave_id]
         .enemy_distribution
         .clone();
@@ -137,7 +361,9 @@ fn spawn_enemy_from_kind(
     }
 
     let value = rng.random_range(0.0..sum_rate);
-    let mut spawn_kind: EnemyKind = EnemyKind::DinoStraight;
+    let Some(mut spawn_kind) = distribution.keys().next().cloned() else {
+        return;
+    };
 
     sum_rate = 0.0;
     for (kind, rate) in distribution.iter() {
@@ -148,57 +374,153 @@ fn spawn_enemy_from_kind(
         }
     }
 
-    let enemy_def: EnemyDefinition;
-    match enemy_db.defs.get(&spawn_kind) {
-        Some(e) => enemy_def = e.clone(),
-        _ => panic!("Unknown Enemy"),
+    // A reload may have removed an enemy the stage still refers to.
+    let Some(enemy_def) = enemy_db.defs.get(&spawn_kind).cloned() else {
+        warn!("unknown enemy \"{}\", skipping the spawn", spawn_kind.0);
+        return;
     };
 
-    match enemy_def.visual {
-        AssetVisual::Static(texture) => {
-            commands.spawn((
-                Sprite::from_image(texture),
-                Transform::from_xyz(x, 340.0, 0.0),
-                Collider {
-                    shape: ColliderShape::Rectangle {
-                        size: enemy_def.collider_size,
-                    },
-                    tag: ColliderTag::Enemy,
-                },
-                Enemy,
-                enemy_def.movement_pattern.clone(),
-                EnemyFireTimer {
-                    timer: Timer::from_seconds(enemy_def.fire_interval, TimerMode::Repeating),
-                },
-            ));
-        }
-        AssetVisual::Animated(anim) => {
-            commands.spawn((
-                Sprite::from_atlas_image(
-                    anim.texture.clone(),
-                    TextureAtlas {
-                        layout: anim.layout.clone(),
-                        index: anim.anim_config.first_sprite_index,
-                    },
-                ),
-                anim.anim_config.clone(),
-                Transform::from_xyz(x, 340.0, 0.0),
-                Collider {
-                    shape: ColliderShape::Rectangle {
-                        size: enemy_def.collider_size,
-                    },
-                    tag: ColliderTag::Enemy,
-                },
-                Enemy,
-                enemy_def.movement_pattern.clone(),
-                EnemyFireTimer {
-                    timer: Timer::from_seconds(enemy_def.fire_interval, TimerMode::Repeating),
-                },
-            ));
+    let wave = &stage_db.settings[stage_id].waves[wave_id];
+    if !wave.formations.is_empty() && rng.random_range(0.0..1.0) < wave.formation_rate {
+        let formation = &wave.formations[rng.random_range(0..wave.formations.len())];
+        spawn_formation(&mut commands, &enemy_def, formation, Vec3::new(x, y, 0.0));
+        return;
+    }
+
+    spawn_enemy(&mut commands, &enemy_def, Vec3::new(x, y, 0.0));
+}
+
+pub fn spawn_timeline_enemies(
+    mut commands: Commands,
+    time: Res<Time>,
+    enemy_db: Res<EnemyDatabase>,
+    mut stage_db: ResMut<StageDatabase>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let stage_index = stage_db.current_index;
+    let wave_index = stage_db.settings[stage_index].current_index;
+    let wave = &mut stage_db.settings[stage_index].waves[wave_index];
+    if wave.mode != WaveMode::Timeline {
+        return;
+    }
+
+    wave.elapsed += time.delta_secs();
+    while wave.next_event < wave.timeline.len()
+        && wave.timeline[wave.next_event].time <= wave.elapsed
+    {
+        spawn_event(&mut commands, &enemy_db, &wave.timeline[wave.next_event]);
+        wave.next_event += 1;
+    }
+}
+
+fn spawn_event(commands: &mut Commands, enemy_db: &EnemyDatabase, event: &SpawnEvent) {
+    let Some(enemy_def) = enemy_db.defs.get(&event.enemy) else {
+        return;
+    };
+
+    let mut enemy_def = enemy_def.clone();
+    if let Some(movement) = &event.movement {
+        enemy_def.movement_pattern = movement.clone();
+    }
+
+    let position = event.position.extend(0.0);
+    match &event.formation {
+        Some(formation) => spawn_formation(commands, &enemy_def, formation, position),
+        None => {
+            spawn_enemy(commands, &enemy_def, position);
         }
     }
 }
 
+pub fn spawn_enemy(commands: &mut Commands, enemy_def: &EnemyDefinition, position: Vec3) -> Entity {
+    let enemy = spawn_enemy_body(commands, enemy_def, position);
+    commands.entity(enemy).insert((
+        enemy_def.movement_pattern.clone(),
+        EnemyMoveState {
+            elapsed: 0.0,
+            origin: position.xy(),
+        },
+    ));
+
+    enemy
+}
+
+/// Spawns an invisible leader that moves with the enemy's movement pattern and one
+/// enemy per formation slot around it.
+pub fn spawn_formation(
+    commands: &mut Commands,
+    enemy_def: &EnemyDefinition,
+    formation: &FormationSetting,
+    position: Vec3,
+) {
+    let leader = commands
+        .spawn((
+            Transform::from_translation(position),
+            enemy_def.movement_pattern.clone(),
+            EnemyMoveState {
+                elapsed: 0.0,
+                origin: position.xy(),
+            },
+            FormationLeader {
+                bonus: formation.bonus,
+                escaped: false,
+                last_position: position.xy(),
+            },
+        ))
+        .id();
+
+    for offset in formation.shape.offsets() {
+        let member = spawn_enemy_body(commands, enemy_def, position + offset.extend(0.0));
+        commands
+            .entity(member)
+            .insert(FormationMember { leader, offset });
+    }
+}
+
+/// Everything an enemy needs except its movement.
+fn spawn_enemy_body(
+    commands: &mut Commands,
+    enemy_def: &EnemyDefinition,
+    position: Vec3,
+) -> Entity {
+    let (sprite, anim_config) = sprite_from_visual(&enemy_def.visual);
+    let mut enemy = commands.spawn((
+        sprite,
+        Transform::from_translation(position),
+        Collider {
+            shape: ColliderShape::Rectangle {
+                size: enemy_def.collider_size,
+            },
+            layer: CollisionLayers::ENEMY,
+            mask: CollisionLayers::PLAYER | CollisionLayers::PLAYER_BULLET,
+        },
+        Enemy,
+        Health {
+            current: enemy_def.max_hp,
+            max: enemy_def.max_hp,
+        },
+        enemy_def.clone(),
+    ));
+
+    if let Some(anim_config) = anim_config {
+        enemy.insert(anim_config);
+    }
+    if enemy_def.bullet.is_some() {
+        enemy.insert((
+            EnemyFireTimer {
+                timer: Timer::from_seconds(enemy_def.fire_interval, TimerMode::Repeating),
+            },
+            BulletEmitter::default(),
+        ));
+    }
+
+    enemy.id()
+}
+
 //fn spawn_enemy(
 //    mut commands: Commands,
 //    mut interval: ResMut<EnemySpawnTimer>,
@@ -247,7 +569,8 @@ fn spawn_enemy_from_kind(
 //            shape: ColliderShape::Rectangle {
 //                size: Vec2::new(30.0, 30.0),
 //            },
-//            tag: ColliderTag::Enemy,
+//            layer: CollisionLayers::ENEMY,
+//            mask: CollisionLayers::PLAYER | CollisionLayers::PLAYER_BULLET,
 //        },
 //        Enemy,
 //        move_pattern,
@@ -260,45 +583,157 @@ fn spawn_enemy_from_kind(
 fn enemy_fire_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(&Transform, &mut EnemyFireTimer)>,
-    assets: Res<GameAssets>,
+    mut query: Query<(
+        &Transform,
+        &mut EnemyFireTimer,
+        &mut BulletEmitter,
+        &EnemyDefinition,
+    )>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
 ) {
-    for (transform, mut timer) in query.iter_mut() {
+    let player_position = player_query.single().ok().map(|t| t.translation.xy());
+
+    for (transform, mut timer, mut emitter, enemy_def) in query.iter_mut() {
+        let Some(bullet_def) = &enemy_def.bullet else {
+            continue;
+        };
+
+        let position = transform.translation;
+        let aim = match player_position {
+            Some(target) => (target - position.xy()).normalize_or(Vec2::NEG_Y),
+            None => Vec2::NEG_Y,
+        };
+
         timer.timer.tick(time.delta());
 
         if timer.timer.finished() {
-            commands.spawn((
-                Sprite::from_image(assets.blue_egg_texture.clone()),
-                //Sprite {
-                //    color: Color::srgb(0.5, 0.0, 0.5),
-                //    custom_size: Some(Vec2::new(3.0, 3.0)),
-                //    ..default()
-                //},
-                Transform {
-                    translation: transform.translation,
-                    ..default()
-                },
-                Collider {
-                    shape: ColliderShape::Rectangle {
-                        size: Vec2::new(30.0, 30.0),
-                    },
-                    tag: ColliderTag::EnemyBullet,
-                },
-                Bullet {
-                    is_player: false,
-                    speed: 400.0,
-                },
-            ));
+            let pattern = match &enemy_def.bullet_pattern {
+                BulletPattern::Burst {
+                    pattern,
+                    shots,
+                    delay,
+                } => {
+                    emitter.burst_left = shots.saturating_sub(1);
+                    emitter.burst_timer = Timer::from_seconds(*delay, TimerMode::Repeating);
+                    pattern.as_ref()
+                }
+                pattern => pattern,
+            };
+
+            for direction in pattern_directions(pattern, aim, &mut emitter) {
+                spawn_enemy_bullet(&mut commands, bullet_def, position, direction);
+            }
 
             timer.timer.reset();
+        } else if emitter.burst_left > 0 {
+            emitter.burst_timer.tick(time.delta());
+
+            if emitter.burst_timer.just_finished() {
+                emitter.burst_left -= 1;
+                if let BulletPattern::Burst { pattern, .. } = &enemy_def.bullet_pattern {
+                    for direction in pattern_directions(pattern, aim, &mut emitter) {
+                        spawn_enemy_bullet(&mut commands, bullet_def, position, direction);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Directions of the bullets in one volley of `pattern`. `aim` points at the player.
+fn pattern_directions(
+    pattern: &BulletPattern,
+    aim: Vec2,
+    emitter: &mut BulletEmitter,
+) -> Vec<Vec2> {
+    match pattern {
+        BulletPattern::Straight => vec![Vec2::NEG_Y],
+        BulletPattern::Aimed => vec![aim],
+        BulletPattern::Spread {
+            count,
+            angle,
+            aimed,
+        } => {
+            let base = if *aimed { aim } else { Vec2::NEG_Y };
+            match count {
+                0 => return Vec::new(),
+                1 => return vec![base],
+                _ => {}
+            }
+
+            let step = angle / (*count - 1) as f32;
+            (0..*count)
+                .map(|i| rotate_degrees(base, -angle / 2.0 + step * i as f32))
+                .collect()
+        }
+        BulletPattern::Ring { count } => ring_directions(*count, 0.0),
+        BulletPattern::Spiral { count, rotation } => {
+            let directions = ring_directions(*count, emitter.spiral_angle);
+            emitter.spiral_angle = (emitter.spiral_angle + rotation) % 360.0;
+            directions
         }
+        // Nested bursts fire their inner pattern once per shot.
+        BulletPattern::Burst { pattern, .. } => pattern_directions(pattern, aim, emitter),
+    }
+}
+
+fn ring_directions(count: u32, offset: f32) -> Vec<Vec2> {
+    let count = count.max(1);
+    let step = 360.0 / count as f32;
+
+    (0..count)
+        .map(|i| rotate_degrees(Vec2::NEG_Y, offset + step * i as f32))
+        .collect()
+}
+
+fn rotate_degrees(direction: Vec2, degrees: f32) -> Vec2 {
+    Vec2::from_angle(degrees.to_radians()).rotate(direction)
+}
+
+fn spawn_enemy_bullet(
+    commands: &mut Commands,
+    bullet_def: &BulletDefinition,
+    position: Vec3,
+    direction: Vec2,
+) {
+    let (sprite, anim_config) = sprite_from_visual(&bullet_def.visual);
+    let mut bullet = commands.spawn((
+        sprite,
+        Transform::from_translation(position),
+        Collider {
+            shape: ColliderShape::Rectangle {
+                size: bullet_def.collider_size,
+            },
+            layer: CollisionLayers::ENEMY_BULLET,
+            mask: CollisionLayers::PLAYER,
+        },
+        Bullet {
+            velocity: direction * bullet_def.speed,
+            damage: bullet_def.damage,
+            piercing: false,
+            hits: Vec::new(),
+        },
+    ));
+    if let Some(anim_config) = anim_config {
+        bullet.insert(anim_config);
     }
 }
 
+type EnemyOrLeader = Or<(With<Enemy>, With<FormationLeader>)>;
+type PlayerOnly = (With<Player>, Without<Enemy>, Without<FormationLeader>);
+
+type EnemyMoveData = (
+    Entity,
+    &'static mut Transform,
+    &'static EnemyMovePattern,
+    &'static mut EnemyMoveState,
+    Has<FormationLeader>,
+);
+
 fn enemy_movement(
     mut commands: Commands,
-    mut enemy_query: Query<(Entity, &mut Transform, &EnemyMovePattern), With<Enemy>>,
-    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    mut enemy_query: Query<EnemyMoveData, EnemyOrLeader>,
+    player_query: Query<&Transform, PlayerOnly>,
     time: Res<Time>,
     stage_db: Res<StageDatabase>,
     //waves: Res<Waves>,
@@ -309,15 +744,16 @@ fn enemy_movement(
 
     let speed: f32 = stage_db.settings[stage_index].waves[wave_index].enemy_speed;
 
-    for (entity, mut transform, pattern) in &mut enemy_query {
+    for (entity, mut transform, pattern, mut state, is_leader) in &mut enemy_query {
+        state.elapsed += time.delta_secs();
+
         match pattern {
             EnemyMovePattern::Straight => {
                 transform.translation.y -= speed * time.delta_secs();
             }
             EnemyMovePattern::Zigzag => {
                 transform.translation.y -= speed * time.delta_secs();
-                transform.translation.x +=
-                    (time.elapsed_secs() * 5.0).sin() * speed * time.delta_secs();
+                transform.translation.x += (state.elapsed * 5.0).sin() * speed * time.delta_secs();
             }
             EnemyMovePattern::Homing => {
                 if let Ok(player) = player_query.single() {
@@ -325,15 +761,96 @@ fn enemy_movement(
                     transform.translation += direction * speed * time.delta_secs();
                 }
             }
+            EnemyMovePattern::Path(path) => match path.offset_at(state.elapsed) {
+                Some(offset) => {
+                    let position = state.origin + offset;
+                    transform.translation.x = position.x;
+                    transform.translation.y = position.y;
+                }
+                None => {
+                    transform.translation.y -= speed * time.delta_secs();
+                }
+            },
         }
 
-        if transform.translation.y <= -380.0 {
+        // Leaders are removed by `formation_system` once their members are gone.
+        if transform.translation.y <= -(playfield::HALF_SIZE.y + DESPAWN_MARGIN) && !is_leader {
             commands.entity(entity).despawn();
         }
     }
 }
 
-fn cleanup_enemies(mut commands: Commands, query: Query<Entity, With<Enemy>>) {
+pub fn formation_system(
+    mut commands: Commands,
+    mut member_query: Query<(Entity, &mut Transform, &FormationMember), Without<FormationLeader>>,
+    mut leader_query: Query<(Entity, &Transform, &mut FormationLeader), Without<FormationMember>>,
+    mut defeated: EventReader<EnemyDefeated>,
+    mut cleared: EventWriter<FormationCleared>,
+) {
+    // The bonus item drops where the last member went down.
+    for event in defeated.read() {
+        let Some(leader) = event.formation else {
+            continue;
+        };
+        if let Ok((_, _, mut leader)) = leader_query.get_mut(leader) {
+            leader.last_position = event.position.xy();
+        }
+    }
+
+    let mut member_counts: HashMap<Entity, u32> = HashMap::new();
+
+    for (entity, mut transform, member) in &mut member_query {
+        let Ok((_, leader_transform, mut leader)) = leader_query.get_mut(member.leader) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        transform.translation = leader_transform.translation + member.offset.extend(0.0);
+
+        if transform.translation.y <= -(playfield::HALF_SIZE.y + DESPAWN_MARGIN) {
+            leader.escaped = true;
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        *member_counts.entry(member.leader).or_insert(0) += 1;
+    }
+
+    for (entity, _, leader) in &leader_query {
+        if member_counts.contains_key(&entity) {
+            continue;
+        }
+
+        if !leader.escaped {
+            cleared.write(FormationCleared {
+                position: leader.last_position.extend(0.0),
+                bonus: leader.bonus,
+            });
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+fn hit_flash_system(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Sprite, &mut HitFlash)>,
+    time: Res<Time>,
+) {
+    for (entity, mut sprite, mut flash) in &mut query {
+        flash.timer.tick(time.delta());
+
+        if flash.timer.finished() {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).try_remove::<HitFlash>();
+        } else {
+            // The color multiplies the texture and each channel is clamped at 1.0, so this
+            // washes bright areas out toward white but leaves dark or saturated ones tinted.
+            sprite.color = Color::srgb(8.0, 8.0, 8.0);
+        }
+    }
+}
+
+fn cleanup_enemies(mut commands: Commands, query: Query<Entity, EnemyOrLeader>) {
     for entity in &query {
         commands.entity(entity).despawn();
     }
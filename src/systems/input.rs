@@ -0,0 +1,267 @@
+use crate::components::input::*;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::fs;
+use std::path::Path;
+
+const INPUT_CONFIG_PATH: &str = "config/input.ron";
+
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_input_map())
+            .init_resource::<RebindRequest>()
+            .add_systems(
+                Update,
+                (controls_panel_input, update_controls_text)
+                    .chain()
+                    .run_if(any_with_component::<ControlsPanel>),
+            )
+            .add_systems(PostUpdate, capture_rebind);
+    }
+}
+
+/// Reads actions from the current `InputMap`, across the keyboard and every connected
+/// gamepad. Everything reads as released while a rebind is waiting for input.
+#[derive(SystemParam)]
+pub struct Actions<'w, 's> {
+    input_map: Res<'w, InputMap>,
+    rebind: Res<'w, RebindRequest>,
+    keyboard: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl Actions<'_, '_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Key(key) => self.keyboard.pressed(*key),
+            Binding::Button(button) => self.gamepads.iter().any(|pad| pad.pressed(*button)),
+        })
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.any_binding(action, |binding| match binding {
+            Binding::Key(key) => self.keyboard.just_pressed(*key),
+            Binding::Button(button) => self.gamepads.iter().any(|pad| pad.just_pressed(*button)),
+        })
+    }
+
+    /// Movement direction with a length of at most 1. The left stick wins over the
+    /// digital bindings once it leaves the deadzone.
+    pub fn movement(&self) -> Vec2 {
+        if self.rebind.action.is_some() {
+            return Vec2::ZERO;
+        }
+
+        for gamepad in &self.gamepads {
+            let stick = gamepad.left_stick();
+            if stick.length() > self.input_map.stick_deadzone {
+                return stick.clamp_length_max(1.0);
+            }
+        }
+
+        let mut direction = Vec2::ZERO;
+        if self.pressed(Action::MoveLeft) {
+            direction.x -= 1.0;
+        }
+        if self.pressed(Action::MoveRight) {
+            direction.x += 1.0;
+        }
+        if self.pressed(Action::MoveDown) {
+            direction.y -= 1.0;
+        }
+        if self.pressed(Action::MoveUp) {
+            direction.y += 1.0;
+        }
+
+        // Normalised so diagonals are no faster than straight movement.
+        direction.normalize_or_zero()
+    }
+
+    fn any_binding(&self, action: Action, test: impl Fn(&Binding) -> bool) -> bool {
+        if self.rebind.action.is_some() {
+            return false;
+        }
+        self.input_map.bindings(action).iter().any(test)
+    }
+}
+
+fn load_input_map() -> InputMap {
+    let Ok(text) = fs::read_to_string(INPUT_CONFIG_PATH) else {
+        return InputMap::default();
+    };
+
+    match ron::from_str::<InputMap>(&text) {
+        Ok(mut input_map) => {
+            // Actions added since the file was saved keep their default bindings.
+            for (action, bindings) in InputMap::default().bindings {
+                input_map.bindings.entry(action).or_insert(bindings);
+            }
+            input_map
+        }
+        Err(err) => {
+            warn!("{}: {}, using the default bindings", INPUT_CONFIG_PATH, err);
+            InputMap::default()
+        }
+    }
+}
+
+fn save_input_map(input_map: &InputMap) {
+    let text = match ron::ser::to_string_pretty(input_map, ron::ser::PrettyConfig::default()) {
+        Ok(text) => text,
+        Err(err) => {
+            error!("could not serialize the input map: {}", err);
+            return;
+        }
+    };
+
+    let dir = Path::new(INPUT_CONFIG_PATH)
+        .parent()
+        .unwrap_or(Path::new("."));
+    if let Err(err) = fs::create_dir_all(dir) {
+        error!("could not create {}: {}", dir.display(), err);
+        return;
+    }
+    if let Err(err) = fs::write(INPUT_CONFIG_PATH, text) {
+        error!("could not write {}: {}", INPUT_CONFIG_PATH, err);
+    }
+}
+
+/// Binds the next key or gamepad button pressed to the requested action and saves the map.
+fn capture_rebind(
+    mut request: ResMut<RebindRequest>,
+    mut input_map: ResMut<InputMap>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) {
+    let Some(action) = request.action else {
+        return;
+    };
+    if !request.armed {
+        request.armed = true;
+        return;
+    }
+
+    let binding = match keyboard.get_just_pressed().next() {
+        Some(key) => Binding::Key(*key),
+        None => match gamepads
+            .iter()
+            .find_map(|pad| pad.get_just_pressed().next().copied())
+        {
+            Some(button) => Binding::Button(button),
+            None => return,
+        },
+    };
+
+    input_map.rebind(action, binding);
+    save_input_map(&input_map);
+    request.action = None;
+    info!("bound {:?} to {:?}", action, binding);
+}
+
+pub fn open_controls_panel(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+            GlobalZIndex(10),
+            ControlsPanel { selected: 0 },
+        ))
+        .with_child((
+            Text::new(""),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            ControlsText,
+        ));
+}
+
+fn controls_panel_input(
+    mut commands: Commands,
+    actions: Actions,
+    mut panel_query: Query<(Entity, &mut ControlsPanel)>,
+    mut request: ResMut<RebindRequest>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let Ok((entity, mut panel)) = panel_query.single_mut() else {
+        return;
+    };
+
+    // One row per action plus "Reset to defaults".
+    let rows = Action::ALL.len() + 1;
+    if actions.just_pressed(Action::MoveUp) {
+        panel.selected = (panel.selected + rows - 1) % rows;
+    }
+    if actions.just_pressed(Action::MoveDown) {
+        panel.selected = (panel.selected + 1) % rows;
+    }
+
+    if actions.just_pressed(Action::Confirm) {
+        match Action::ALL.get(panel.selected) {
+            Some(action) => request.start(*action),
+            None => {
+                *input_map = InputMap::default();
+                save_input_map(&input_map);
+            }
+        }
+    } else if actions.just_pressed(Action::Back) {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn update_controls_text(
+    panel_query: Query<&ControlsPanel>,
+    mut text_query: Query<&mut Text, With<ControlsText>>,
+    input_map: Res<InputMap>,
+    request: Res<RebindRequest>,
+) {
+    let Ok(panel) = panel_query.single() else {
+        return;
+    };
+
+    let mut text = String::from("Controls\n\n");
+    for (i, action) in Action::ALL.iter().enumerate() {
+        let cursor = if i == panel.selected { ">" } else { " " };
+        let bindings = if request.action == Some(*action) {
+            String::from("press a key or button...")
+        } else {
+            input_map
+                .bindings(*action)
+                .iter()
+                .map(binding_name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        text = format!("{}{} {:?}: {}\n", text, cursor, action, bindings);
+    }
+
+    let cursor = if panel.selected == Action::ALL.len() {
+        ">"
+    } else {
+        " "
+    };
+    text = format!(
+        "{}\n{} Reset to defaults\n\nConfirm: rebind   Back: close",
+        text, cursor
+    );
+
+    for mut span in &mut text_query {
+        span.0 = text.clone();
+    }
+}
+
+fn binding_name(binding: &Binding) -> String {
+    match binding {
+        Binding::Key(key) => format!("{:?}", key),
+        Binding::Button(button) => format!("Pad {:?}", button),
+    }
+}
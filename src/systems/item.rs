@@ -1,43 +1,135 @@
 use crate::GameState;
+use crate::PlayState;
 use crate::components::assets::*;
 use crate::components::collider::*;
+use crate::components::enemy::EnemyDefeated;
+use crate::components::formation::FormationCleared;
 use crate::components::item::*;
 use crate::components::player::*;
+use crate::components::weapon::*;
+use crate::systems::collision::{apply_collision_damage, collision_system};
+use crate::systems::enemy::formation_system;
 use bevy::prelude::*;
+use rand::Rng;
 
 pub struct ItemPlugin;
 
 impl Plugin for ItemPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::Playing), cleanup_items);
+        app.add_systems(
+            Update,
+            pickup_items
+                .after(collision_system)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(
+            Update,
+            drop_items
+                .after(apply_collision_damage)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(
+            Update,
+            drop_formation_items
+                .after(formation_system)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(OnExit(GameState::Playing), cleanup_items);
     }
 }
 
+const MIN_SHOOT_INTERVAL: f32 = 0.05;
+
 pub fn spawn_item(
     commands: &mut Commands,
     assets: &Res<GameAssets>,
     item_type: ItemType,
     position: Vec3,
 ) {
-    let asset = match item_type {
-        ItemType::RapidFire => assets.rapid_fire_texture.clone(),
-        ItemType::PiercingShot => assets.piercing_shot_texture.clone(),
-        ItemType::Heal => assets.apple_texture.clone(),
+    let sprite = match item_type {
+        ItemType::PowerUp => Sprite::from_image(assets.power_up_texture.clone()),
+        ItemType::PiercingShot => Sprite::from_image(assets.piercing_shot_texture.clone()),
+        ItemType::Heal => Sprite::from_image(assets.apple_texture.clone()),
+        ItemType::Bomb => Sprite::from_image(assets.bomb_texture.clone()),
+        ItemType::Weapon(kind) => Sprite {
+            color: kind.color(),
+            ..Sprite::from_image(assets.weapon_texture.clone())
+        },
     };
 
     commands.spawn((
-        Sprite::from_image(asset.clone()),
+        sprite,
         Transform::from_translation(position),
         Collider {
             shape: ColliderShape::Rectangle {
                 size: Vec2::new(20.0, 20.0),
             },
-            tag: ColliderTag::Item,
+            layer: CollisionLayers::ITEM,
+            mask: CollisionLayers::PLAYER,
         },
         item_type,
     ));
 }
 
+pub fn random_item_type() -> ItemType {
+    let mut rng = rand::rng();
+    match rng.random_range(0..5) {
+        0 => ItemType::PowerUp,
+        1 => ItemType::PiercingShot,
+        2 => ItemType::Heal,
+        3 => ItemType::Bomb,
+        4 => ItemType::Weapon(WeaponKind::ALL[rng.random_range(0..WeaponKind::ALL.len())]),
+        _ => ItemType::PowerUp,
+    }
+}
+
+fn pickup_items(
+    mut commands: Commands,
+    mut events: EventReader<CollisionEvent>,
+    item_query: Query<&ItemType>,
+    mut player_query: Query<&mut Player>,
+) {
+    for event in events.read() {
+        let Some((player_entity, item_entity)) =
+            event.between(CollisionLayers::PLAYER, CollisionLayers::ITEM)
+        else {
+            continue;
+        };
+
+        if let (Ok(item), Ok(mut player)) = (
+            item_query.get(item_entity),
+            player_query.get_mut(player_entity),
+        ) {
+            apply_item_effect(&mut player, *item);
+            commands.entity(item_entity).despawn();
+        }
+    }
+}
+
+fn drop_items(
+    mut commands: Commands,
+    mut events: EventReader<EnemyDefeated>,
+    assets: Res<GameAssets>,
+) {
+    let mut rng = rand::rng();
+    for event in events.read() {
+        if rng.random_range(0..100) < 30 {
+            spawn_item(&mut commands, &assets, random_item_type(), event.position);
+        }
+    }
+}
+
+/// A cleared formation always drops an item.
+fn drop_formation_items(
+    mut commands: Commands,
+    mut events: EventReader<FormationCleared>,
+    assets: Res<GameAssets>,
+) {
+    for event in events.read() {
+        spawn_item(&mut commands, &assets, random_item_type(), event.position);
+    }
+}
+
 pub fn cleanup_items(mut commands: Commands, query: Query<Entity, With<ItemType>>) {
     for entity in &query {
         commands.entity(entity).despawn();
@@ -46,8 +138,10 @@ pub fn cleanup_items(mut commands: Commands, query: Query<Entity, With<ItemType>
 
 pub fn apply_item_effect(player: &mut Player, item: ItemType) {
     match item {
-        ItemType::RapidFire => {
-            player.shoot_interval *= 0.5;
+        ItemType::PowerUp => {
+            if !player.weapon.power_up() {
+                player.shoot_interval = (player.shoot_interval * 0.5).max(MIN_SHOOT_INTERVAL);
+            }
         }
         ItemType::PiercingShot => {
             player.piercing = true;
@@ -58,5 +152,16 @@ pub fn apply_item_effect(player: &mut Player, item: ItemType) {
                 player.hp = player.max_hp;
             }
         }
+        ItemType::Bomb => {
+            player.bombs = (player.bombs + 1).min(player.max_bombs);
+        }
+        ItemType::Weapon(kind) => {
+            if player.weapon.kind == kind {
+                player.weapon.power_up();
+            } else {
+                // Switching keeps the level, so picking up a new gun is never a downgrade.
+                player.weapon.kind = kind;
+            }
+        }
     }
 }
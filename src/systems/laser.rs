@@ -0,0 +1,109 @@
+use crate::GameState;
+use crate::PlayState;
+use crate::components::collider::*;
+use crate::components::input::Action;
+use crate::components::player::Player;
+use crate::components::playfield;
+use crate::components::weapon::*;
+use crate::systems::collision::collision_system;
+use crate::systems::input::Actions;
+use crate::systems::player::player_movement;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+pub struct LaserPlugin;
+
+impl Plugin for LaserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_laser
+                .after(player_movement)
+                .before(collision_system)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(OnExit(GameState::Playing), cleanup_laser);
+    }
+}
+
+type BeamData = (
+    Entity,
+    &'static mut Transform,
+    &'static mut Sprite,
+    &'static mut Collider,
+    &'static mut LaserBeam,
+);
+type BeamTargetFilter = (Without<LaserBeam>, Without<Player>);
+
+/// Keeps a beam out while Fire is held with the laser equipped, cut short at the
+/// first enemy in its path, and works out how much damage it deals this frame.
+fn update_laser(
+    mut commands: Commands,
+    actions: Actions,
+    player_query: Query<(&Transform, &Player), Without<LaserBeam>>,
+    mut beam_query: Query<BeamData, Without<Player>>,
+    target_query: Query<(&Transform, &Collider), BeamTargetFilter>,
+    time: Res<Time>,
+) {
+    let firing = player_query.single().ok().filter(|(_, player)| {
+        player.weapon.kind == WeaponKind::Laser && actions.pressed(Action::Fire)
+    });
+    let Some((player_transform, player)) = firing else {
+        for (entity, ..) in &beam_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let origin = player_transform.translation.xy() + Vec2::new(0.0, 30.0);
+    let (width, dps) = player.weapon.beam(player.bullet_damage, player.focused);
+    let max_length = (playfield::HALF_SIZE.y - origin.y).max(0.0);
+    let length = target_query
+        .iter()
+        .filter(|(_, collider)| collider.layer.intersects(CollisionLayers::ENEMY))
+        .filter_map(|(transform, collider)| {
+            collider
+                .world_shape(transform)
+                .sweep_distance(origin, Vec2::Y, width / 2.0, max_length)
+        })
+        .fold(max_length, f32::min);
+
+    let shape = ColliderShape::Segment { length, width };
+    let size = Vec2::new(width, length);
+    let translation = origin.extend(1.0);
+
+    match beam_query.single_mut() {
+        Ok((_, mut transform, mut sprite, mut collider, mut beam)) => {
+            transform.translation = translation;
+            sprite.custom_size = Some(size);
+            collider.shape = shape;
+
+            beam.carry += dps * time.delta_secs();
+            beam.damage = beam.carry.floor() as u32;
+            beam.carry -= beam.damage as f32;
+        }
+        Err(_) => {
+            commands.spawn((
+                Sprite {
+                    color: player.weapon.kind.color().with_alpha(0.8),
+                    custom_size: Some(size),
+                    anchor: Anchor::BottomCenter,
+                    ..default()
+                },
+                Transform::from_translation(translation),
+                Collider {
+                    shape,
+                    layer: CollisionLayers::PLAYER_BULLET,
+                    mask: CollisionLayers::ENEMY,
+                },
+                LaserBeam::default(),
+            ));
+        }
+    }
+}
+
+fn cleanup_laser(mut commands: Commands, query: Query<Entity, With<LaserBeam>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
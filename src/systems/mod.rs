@@ -1,10 +1,17 @@
 pub mod animation;
 pub mod assets;
+pub mod bomb;
+pub mod boss;
 pub mod bullet;
+pub mod charge;
 pub mod collision;
+pub mod debug;
 pub mod enemy;
 pub mod explosion;
+pub mod input;
 pub mod item;
+pub mod laser;
+pub mod parry;
 pub mod player;
 pub mod score;
 pub mod sets;
@@ -0,0 +1,136 @@
+use crate::PlayState;
+use crate::components::assets::*;
+use crate::components::bullet::Bullet;
+use crate::components::collider::*;
+use crate::components::enemy::Enemy;
+use crate::components::input::Action;
+use crate::components::player::*;
+use crate::systems::collision::collision_system;
+use crate::systems::input::Actions;
+use crate::systems::player::player_movement;
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+pub struct ParryPlugin;
+
+impl Plugin for ParryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BulletParried>()
+            .add_systems(Update, start_parry.run_if(in_state(PlayState::Running)))
+            .add_systems(
+                Update,
+                tick_parry
+                    .after(start_parry)
+                    .run_if(in_state(PlayState::Running)),
+            )
+            .add_systems(
+                Update,
+                reflect_bullets
+                    .after(tick_parry)
+                    .after(player_movement)
+                    .before(collision_system)
+                    .run_if(in_state(PlayState::Running)),
+            );
+    }
+}
+
+const REFLECT_SPEED_SCALE: f32 = 1.5;
+const REFLECT_MIN_SPEED: f32 = 300.0;
+
+fn start_parry(
+    mut commands: Commands,
+    actions: Actions,
+    mut query: Query<(Entity, &mut Parry)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    audio: Res<bevy_kira_audio::prelude::Audio>,
+    assets: Res<GameAssets>,
+) {
+    if !actions.just_pressed(Action::Parry) {
+        return;
+    }
+    let Ok((entity, mut parry)) = query.single_mut() else {
+        return;
+    };
+    if !parry.is_ready() {
+        return;
+    }
+
+    parry.start();
+    commands.entity(entity).with_child((
+        Mesh2d(meshes.add(Annulus::new(26.0, 30.0))),
+        MeshMaterial2d(materials.add(Color::srgba(0.6, 1.0, 1.0, 0.8))),
+        Transform::from_xyz(0.0, 0.0, 1.0),
+        ParryRing,
+    ));
+    audio
+        .play(assets.shoot_sound.clone())
+        .with_volume(0.3)
+        .with_playback_rate(2.0);
+}
+
+fn tick_parry(
+    mut commands: Commands,
+    mut query: Query<&mut Parry>,
+    ring_query: Query<Entity, With<ParryRing>>,
+    time: Res<Time>,
+) {
+    for mut parry in &mut query {
+        parry.window.tick(time.delta());
+        parry.cooldown.tick(time.delta());
+
+        if !parry.is_active() {
+            for entity in &ring_query {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Turns enemy bullets touching the player's body during a parry window into player
+/// bullets aimed at the nearest enemy. Runs before `collision_system`, so a reflected
+/// bullet never reaches the core.
+fn reflect_bullets(
+    player_query: Query<(&Transform, &Collider, &Player, &Parry)>,
+    mut bullet_query: Query<
+        (&mut Transform, &mut Collider, &mut Bullet, &mut Sprite),
+        Without<Player>,
+    >,
+    enemy_query: Query<&Transform, (With<Enemy>, Without<Bullet>)>,
+    mut parried: EventWriter<BulletParried>,
+) {
+    let Ok((player_transform, player_collider, player, parry)) = player_query.single() else {
+        return;
+    };
+    if !parry.is_active() {
+        return;
+    }
+    let body = player_collider.world_shape(player_transform);
+
+    for (mut transform, mut collider, mut bullet, mut sprite) in &mut bullet_query {
+        if collider.layer != CollisionLayers::ENEMY_BULLET
+            || !collider.world_shape(&transform).overlaps(&body)
+        {
+            continue;
+        }
+
+        let position = transform.translation;
+        let direction = enemy_query
+            .iter()
+            .map(|enemy| enemy.translation.xy() - position.xy())
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+            .and_then(|offset| offset.try_normalize())
+            .unwrap_or(Vec2::Y);
+        let speed = bullet.velocity.length().max(REFLECT_MIN_SPEED) * REFLECT_SPEED_SCALE;
+
+        collider.layer = CollisionLayers::PLAYER_BULLET;
+        collider.mask = CollisionLayers::ENEMY;
+        bullet.velocity = direction * speed;
+        transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_to(bullet.velocity));
+        bullet.damage = bullet.damage.max(player.bullet_damage) * 2;
+        bullet.hits.clear();
+        sprite.color = Color::srgb(0.6, 1.0, 1.0);
+
+        parried.write(BulletParried);
+    }
+}
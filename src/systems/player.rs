@@ -1,12 +1,22 @@
 use crate::GameState;
+use crate::PlayState;
 use crate::components::assets::*;
 use crate::components::collider::*;
+use crate::components::input::Action;
 use crate::components::player::*;
+use crate::components::playfield;
+use crate::components::weapon::*;
+use crate::systems::collision::collision_system;
+use crate::systems::input::Actions;
 use crate::systems::sets::MySystemSet;
 use bevy::prelude::*;
 
 pub struct PlayerPlugin;
 
+const PLAYER_CORE_RADIUS: f32 = 3.0;
+/// How far the player's centre stays inside the edges of the playfield.
+const PLAYER_EDGE_MARGIN: Vec2 = Vec2::new(15.0, 20.0);
+
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
@@ -17,41 +27,119 @@ impl Plugin for PlayerPlugin {
             OnEnter(GameState::Playing),
             spawn_hp.after(MySystemSet::LoadAssets),
         )
-        .add_systems(Update, update_heart.run_if(in_state(GameState::Playing)))
-        .add_systems(Update, player_movement.run_if(in_state(GameState::Playing)))
+        .add_systems(Update, update_heart.run_if(in_state(PlayState::Running)))
+        .add_systems(Update, player_focus.run_if(in_state(PlayState::Running)))
         .add_systems(
             Update,
-            player_invincible_timer_system.run_if(in_state(GameState::Playing)),
+            player_movement
+                .after(player_focus)
+                .run_if(in_state(PlayState::Running)),
         )
         .add_systems(
             Update,
-            player_blink_system.run_if(in_state(GameState::Playing)),
+            player_core_follow
+                .after(player_movement)
+                .before(collision_system)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(
+            Update,
+            player_invincible_timer_system.run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(
+            Update,
+            player_blink_system.run_if(in_state(PlayState::Running)),
         )
         .add_systems(OnExit(GameState::Playing), cleanup_player)
         .add_systems(OnExit(GameState::Playing), cleanup_heart);
     }
 }
 
-fn spawn_player(mut commands: Commands, assets: Res<GameAssets>) {
+fn spawn_player(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let position = Vec3::new(0.0, -300.0, 0.0);
+
+    // The sprite-sized box only picks up items; damage goes through the core.
+    let player = commands
+        .spawn((
+            Sprite::from_image(assets.player_texture.clone()),
+            Transform::from_translation(position),
+            Collider {
+                shape: ColliderShape::Rectangle {
+                    size: Vec2::new(30.0, 40.0),
+                },
+                layer: CollisionLayers::PLAYER,
+                mask: CollisionLayers::ITEM,
+            },
+            Player {
+                max_hp: 3,
+                hp: 3,
+                invincible_timer: Timer::from_seconds(1.0, TimerMode::Once),
+                shoot_interval: 0.2,
+                bullet_damage: 1,
+                piercing: false,
+                focused: false,
+                speed: 500.0,
+                focus_speed: 200.0,
+                weapon: Weapon::new(WeaponKind::Spread),
+                bombs: 2,
+                max_bombs: 5,
+            },
+            Parry::default(),
+        ))
+        .id();
+
     commands.spawn((
-        Sprite::from_image(assets.player_texture.clone()),
-        Transform::from_xyz(0.0, -300.0, 0.0),
+        Mesh2d(meshes.add(Circle::new(PLAYER_CORE_RADIUS))),
+        MeshMaterial2d(materials.add(Color::WHITE)),
+        Transform::from_translation(position + Vec3::Z),
+        Visibility::Hidden,
         Collider {
-            shape: ColliderShape::Rectangle {
-                size: Vec2::new(30.0, 40.0),
+            shape: ColliderShape::Circle {
+                radius: PLAYER_CORE_RADIUS,
             },
-            tag: ColliderTag::Player,
+            layer: CollisionLayers::PLAYER,
+            mask: CollisionLayers::ENEMY | CollisionLayers::ENEMY_BULLET,
         },
-        Player {
-            max_hp: 3,
-            hp: 3,
-            invincible_timer: Timer::from_seconds(1.0, TimerMode::Once),
-            shoot_interval: 0.2,
-            piercing: false,
+        PlayerCore {
+            player,
+            visible: true,
         },
     ));
 }
 
+fn player_focus(actions: Actions, mut query: Query<&mut Player>) {
+    for mut player in &mut query {
+        player.focused = actions.pressed(Action::Focus);
+    }
+}
+
+fn player_core_follow(
+    mut commands: Commands,
+    mut core_query: Query<(Entity, &mut Transform, &mut Visibility, &PlayerCore), Without<Player>>,
+    player_query: Query<(&Transform, &Player)>,
+) {
+    for (entity, mut transform, mut visibility, core) in &mut core_query {
+        match player_query.get(core.player) {
+            Ok((player_transform, player)) => {
+                transform.translation = player_transform.translation + Vec3::Z;
+                *visibility = if core.visible && player.focused {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                };
+            }
+            Err(_) => {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
 fn player_invincible_timer_system(time: Res<Time>, mut query: Query<&mut Player>) {
     for mut player in &mut query {
         if player.invincible_timer.remaining_secs() > 0.0 {
@@ -89,49 +177,20 @@ fn spawn_hp(mut commands: Commands, assets: Res<GameAssets>, query: Query<&Playe
     }
 }
 
-fn player_movement(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mut query: Query<&mut Transform, With<Player>>,
+pub fn player_movement(
+    actions: Actions,
+    mut query: Query<(&mut Transform, &Player)>,
     time: Res<Time>,
 ) {
-    const SPEED: f32 = 500.0;
-
-    for mut transform in &mut query {
-        let mut x_direction = 0.0;
-        let mut y_direction = 0.0;
-        if keyboard.pressed(KeyCode::ArrowLeft)
-            || keyboard.pressed(KeyCode::KeyA)
-            || keyboard.pressed(KeyCode::KeyH)
-        {
-            x_direction -= 1.0;
-        }
-
-        if keyboard.pressed(KeyCode::ArrowRight)
-            || keyboard.pressed(KeyCode::KeyD)
-            || keyboard.pressed(KeyCode::KeyL)
-        {
-            x_direction += 1.0;
-        }
-
-        if keyboard.pressed(KeyCode::ArrowDown)
-            || keyboard.pressed(KeyCode::KeyS)
-            || keyboard.pressed(KeyCode::KeyJ)
-        {
-            y_direction -= 1.0;
-        }
-
-        if keyboard.pressed(KeyCode::ArrowUp)
-            || keyboard.pressed(KeyCode::KeyW)
-            || keyboard.pressed(KeyCode::KeyK)
-        {
-            y_direction += 1.0;
-        }
+    let bounds = playfield::HALF_SIZE - PLAYER_EDGE_MARGIN;
+    for (mut transform, player) in &mut query {
+        let velocity = actions.movement() * player.current_speed();
 
-        transform.translation.x += x_direction * SPEED * time.delta_secs();
-        transform.translation.x = transform.translation.x.clamp(-225.0, 225.0);
+        transform.translation.x += velocity.x * time.delta_secs();
+        transform.translation.x = transform.translation.x.clamp(-bounds.x, bounds.x);
 
-        transform.translation.y += y_direction * SPEED * time.delta_secs();
-        transform.translation.y = transform.translation.y.clamp(-340.0, 340.0);
+        transform.translation.y += velocity.y * time.delta_secs();
+        transform.translation.y = transform.translation.y.clamp(-bounds.y, bounds.y);
     }
 }
 
@@ -163,7 +222,9 @@ fn update_heart(
     }
 }
 
-fn cleanup_player(mut commands: Commands, query: Query<Entity, With<Player>>) {
+type PlayerOrCore = Or<(With<Player>, With<PlayerCore>)>;
+
+fn cleanup_player(mut commands: Commands, query: Query<Entity, PlayerOrCore>) {
     for entity in &query {
         commands.entity(entity).despawn();
     }
@@ -1,6 +1,12 @@
 use crate::GameState;
+use crate::PlayState;
+use crate::components::enemy::EnemyDefeated;
+use crate::components::formation::FormationCleared;
+use crate::components::player::BulletParried;
 use crate::components::score::Score;
 use crate::components::score::ScoreText;
+use crate::systems::collision::apply_collision_damage;
+use crate::systems::enemy::formation_system;
 use crate::systems::sets::MySystemSet;
 use bevy::prelude::*;
 
@@ -16,7 +22,23 @@ impl Plugin for ScorePlugin {
             OnEnter(GameState::Playing),
             spawn_score.after(MySystemSet::LoadAssets),
         )
-        .add_systems(Update, update_score.run_if(in_state(GameState::Playing)))
+        .add_systems(
+            Update,
+            score_defeated_enemies
+                .after(apply_collision_damage)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(
+            Update,
+            score_cleared_formations
+                .after(formation_system)
+                .run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(
+            Update,
+            score_parried_bullets.run_if(in_state(PlayState::Running)),
+        )
+        .add_systems(Update, update_score.run_if(in_state(PlayState::Running)))
         .add_systems(OnExit(GameState::Playing), despawn_score);
     }
 }
@@ -31,6 +53,27 @@ fn spawn_score(mut commands: Commands) {
         .with_child((TextSpan::default(), ScoreText));
 }
 
+fn score_defeated_enemies(mut events: EventReader<EnemyDefeated>, mut score: ResMut<Score>) {
+    for _ in events.read() {
+        score.score += 100;
+    }
+}
+
+fn score_cleared_formations(mut events: EventReader<FormationCleared>, mut score: ResMut<Score>) {
+    for event in events.read() {
+        score.score += event.bonus;
+    }
+}
+
+/// Points for every enemy bullet sent back with a parry.
+const PARRY_SCORE: u32 = 50;
+
+fn score_parried_bullets(mut events: EventReader<BulletParried>, mut score: ResMut<Score>) {
+    for _ in events.read() {
+        score.score += PARRY_SCORE;
+    }
+}
+
 fn update_score(mut query: Query<&mut TextSpan, With<ScoreText>>, score: Res<Score>) {
     for mut span in &mut query {
         **span = format!("{}", score.score);